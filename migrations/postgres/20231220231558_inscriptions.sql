@@ -0,0 +1,15 @@
+-- psql -f migrations/postgres/20231220231558_inscriptions.sql
+
+-- A table to store inscriptions
+CREATE TABLE IF NOT EXISTS inscriptions
+(
+    id                  BIGSERIAL PRIMARY KEY,
+    -- sender address of the transaction
+    sender              TEXT NOT NULL,
+    -- chain id of the transaction
+    chain_id            BIGINT NOT NULL,
+    -- hash of the transaction
+    tx_hash             TEXT NOT NULL,
+    -- inscription call data
+    calldata            TEXT NOT NULL
+);
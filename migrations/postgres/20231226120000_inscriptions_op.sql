@@ -0,0 +1,4 @@
+-- psql -f migrations/postgres/20231226120000_inscriptions_op.sql
+
+-- the inscription operation of the transaction, all previous entries were mints
+ALTER TABLE inscriptions ADD COLUMN op TEXT NOT NULL DEFAULT 'mint';
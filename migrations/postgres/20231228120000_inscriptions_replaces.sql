@@ -0,0 +1,4 @@
+-- psql -f migrations/postgres/20231228120000_inscriptions_replaces.sql
+
+-- hash of the original transaction, if this transaction replaced it at the same nonce
+ALTER TABLE inscriptions ADD COLUMN replaces TEXT;
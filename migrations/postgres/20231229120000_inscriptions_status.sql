@@ -0,0 +1,4 @@
+-- psql -f migrations/postgres/20231229120000_inscriptions_status.sql
+
+-- status of the transaction receipt, 1 if it succeeded and 0 if it reverted, unknown for older entries
+ALTER TABLE inscriptions ADD COLUMN status BIGINT;
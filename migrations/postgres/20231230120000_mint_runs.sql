@@ -0,0 +1,39 @@
+-- psql -f migrations/postgres/20231230120000_mint_runs.sql
+
+-- A table to store mint runs, so interrupted runs can be resumed
+CREATE TABLE IF NOT EXISTS mint_runs
+(
+    id                  BIGSERIAL PRIMARY KEY,
+    -- sender address of the mints
+    sender              TEXT NOT NULL,
+    -- chain id of the mints
+    chain_id            BIGINT NOT NULL,
+    -- the mint message as given, either a data URI or the bare JSON payload
+    message             TEXT NOT NULL,
+    -- the data URI header the message is encoded with
+    data_uri            TEXT NOT NULL,
+    -- whether the message is sent unchanged
+    raw                 BOOLEAN NOT NULL,
+    -- the template of the mint id, if any
+    id_template         TEXT,
+    -- the number of mints to send
+    transactions        BIGINT NOT NULL
+);
+
+-- A table to store every signed mint transaction, recorded before it's broadcast
+CREATE TABLE IF NOT EXISTS mint_transactions
+(
+    id                  BIGSERIAL PRIMARY KEY,
+    -- the run of the transaction
+    run_id              BIGINT NOT NULL REFERENCES mint_runs (id),
+    -- the index of the mint in its run, kept by replacements and resends
+    n                   BIGINT NOT NULL,
+    -- nonce of the transaction
+    nonce               BIGINT NOT NULL,
+    -- hash of the transaction
+    tx_hash             TEXT NOT NULL UNIQUE,
+    -- the signed transaction
+    raw                 TEXT NOT NULL,
+    -- pending, confirmed, reverted, replaced or dropped
+    state               TEXT NOT NULL DEFAULT 'pending'
+);
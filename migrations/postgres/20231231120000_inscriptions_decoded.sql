@@ -0,0 +1,25 @@
+-- psql -f migrations/postgres/20231231120000_inscriptions_decoded.sql
+
+-- nonce of the transaction, unknown for older entries
+ALTER TABLE inscriptions ADD COLUMN nonce BIGINT;
+-- number of the block the transaction was mined in
+ALTER TABLE inscriptions ADD COLUMN block_number BIGINT;
+-- gas used by the transaction
+ALTER TABLE inscriptions ADD COLUMN gas_used BIGINT;
+-- price paid per unit of gas in wei, as decimal string
+ALTER TABLE inscriptions ADD COLUMN effective_gas_price TEXT;
+-- unix timestamp of the block the transaction was mined in
+ALTER TABLE inscriptions ADD COLUMN timestamp BIGINT;
+-- protocol of the inscription, like `fair-20`
+ALTER TABLE inscriptions ADD COLUMN protocol TEXT;
+-- token of the inscription
+ALTER TABLE inscriptions ADD COLUMN tick TEXT;
+-- amount of the inscription as decimal string, the total for transfers
+ALTER TABLE inscriptions ADD COLUMN amount TEXT;
+
+-- calldata was stored as `Bytes(0x..)`, all hex values are now stored as `0x..`
+UPDATE inscriptions SET calldata = substr(calldata, 7, length(calldata) - 7) WHERE calldata LIKE 'Bytes(%)';
+
+-- keep the first entry of transactions that were stored more than once
+DELETE FROM inscriptions WHERE id NOT IN (SELECT MIN(id) FROM inscriptions GROUP BY chain_id, tx_hash);
+CREATE UNIQUE INDEX IF NOT EXISTS inscriptions_chain_id_tx_hash ON inscriptions (chain_id, tx_hash);
@@ -0,0 +1,39 @@
+-- sqlite3 migrations/20231230120000_mint_runs.sql
+
+-- A table to store mint runs, so interrupted runs can be resumed
+CREATE TABLE IF NOT EXISTS mint_runs
+(
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- sender address of the mints
+    sender              BLOB NOT NULL,
+    -- chain id of the mints
+    chain_id            INTEGER NOT NULL,
+    -- the mint message as given, either a data URI or the bare JSON payload
+    message             TEXT NOT NULL,
+    -- the data URI header the message is encoded with
+    data_uri            TEXT NOT NULL,
+    -- whether the message is sent unchanged
+    raw                 BOOLEAN NOT NULL,
+    -- the template of the mint id, if any
+    id_template         TEXT,
+    -- the number of mints to send
+    transactions        INTEGER NOT NULL
+);
+
+-- A table to store every signed mint transaction, recorded before it's broadcast
+CREATE TABLE IF NOT EXISTS mint_transactions
+(
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- the run of the transaction
+    run_id              INTEGER NOT NULL REFERENCES mint_runs (id),
+    -- the index of the mint in its run, kept by replacements and resends
+    n                   INTEGER NOT NULL,
+    -- nonce of the transaction
+    nonce               INTEGER NOT NULL,
+    -- hash of the transaction
+    tx_hash             BLOB NOT NULL UNIQUE,
+    -- the signed transaction
+    raw                 BLOB NOT NULL,
+    -- pending, confirmed, reverted, replaced or dropped
+    state               TEXT NOT NULL DEFAULT 'pending'
+);
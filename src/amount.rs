@@ -0,0 +1,258 @@
+//! Token amounts
+//!
+//! Amounts are encoded as decimal strings, but some inscriptions use plain JSON numbers, so both
+//! are accepted when decoding.
+//!
+//! Note: JSON numbers that don't fit into a `u64` are parsed as floats by `serde_json` and lose
+//! precision, so these are rejected and must be encoded as decimal strings instead.
+
+use crate::InscriptionError;
+use ethers::types::{I256, U256};
+use serde::{de, Deserializer, Serializer};
+use serde_json::Value;
+use std::{fmt, marker::PhantomData};
+
+/// Formats an amount of base units as human-readable units with the given number of decimals.
+///
+/// Trailing zeros of the fractional part are removed, e.g. `1500` with `3` decimals is `1.5`.
+pub fn format_units(amount: U256, decimals: u8) -> String {
+    let digits = amount.to_string();
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return digits;
+    }
+    let digits = format!("{digits:0>width$}", width = decimals + 1);
+    let (int, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        int.to_string()
+    } else {
+        format!("{int}.{fraction}")
+    }
+}
+
+/// An integer that can be decoded from a decimal string or a JSON number
+trait Decimal: Sized {
+    /// Whether the decimal string may have a sign
+    const SIGNED: bool;
+
+    fn from_u128(v: u128) -> Self;
+
+    fn from_i128(v: i128) -> Option<Self>;
+
+    fn try_from_dec_str(v: &str) -> Option<Self>;
+
+    /// Parses a decimal string, distinguishing overflows from invalid amounts
+    fn from_dec_str(v: &str) -> Result<Self, InscriptionError> {
+        Self::try_from_dec_str(v).ok_or_else(|| {
+            let digits = if Self::SIGNED { v.strip_prefix(['-', '+']).unwrap_or(v) } else { v };
+            if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+                InscriptionError::AmountOverflow(v.to_string())
+            } else {
+                InscriptionError::InvalidAmount(v.to_string())
+            }
+        })
+    }
+}
+
+impl Decimal for U256 {
+    const SIGNED: bool = false;
+
+    fn from_u128(v: u128) -> Self {
+        U256::from(v)
+    }
+
+    fn from_i128(v: i128) -> Option<Self> {
+        u128::try_from(v).ok().map(U256::from)
+    }
+
+    fn try_from_dec_str(v: &str) -> Option<Self> {
+        U256::from_dec_str(v).ok()
+    }
+}
+
+impl Decimal for I256 {
+    const SIGNED: bool = true;
+
+    fn from_u128(v: u128) -> Self {
+        I256::from_raw(U256::from(v))
+    }
+
+    fn from_i128(v: i128) -> Option<Self> {
+        Some(I256::from(v))
+    }
+
+    fn try_from_dec_str(v: &str) -> Option<Self> {
+        I256::from_dec_str(v).ok()
+    }
+}
+
+/// Returns the error of an amount that fails to decode, if any.
+///
+/// Serde only keeps the message of a decoding error, so this recovers the [InscriptionError] of
+/// an invalid amount.
+fn amount_error<T: Decimal>(amount: &Value) -> Option<InscriptionError> {
+    match amount {
+        Value::String(v) => T::from_dec_str(v.trim()).err(),
+        Value::Number(v) if v.is_u64() => None,
+        Value::Number(v) => match v.as_i64() {
+            Some(v) if T::from_i128(v.into()).is_some() => None,
+            _ => Some(InscriptionError::InvalidAmount(v.to_string())),
+        },
+        other => Some(InscriptionError::InvalidAmount(other.to_string())),
+    }
+}
+
+/// Returns the error of an unsigned amount that fails to decode, if any
+pub(crate) fn unsigned_amount_error(amount: &Value) -> Option<InscriptionError> {
+    amount_error::<U256>(amount)
+}
+
+/// Returns the error of a signed amount that fails to decode, if any
+pub(crate) fn signed_amount_error(amount: &Value) -> Option<InscriptionError> {
+    amount_error::<I256>(amount)
+}
+
+/// Visits a decimal string or an integer JSON number
+struct DecimalVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Decimal> de::Visitor<'de> for DecimalVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a decimal string or an integer")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        self.visit_u128(v.into())
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        self.visit_i128(v.into())
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        Ok(T::from_u128(v))
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        T::from_i128(v).ok_or_else(|| E::custom(format!("amount must not be negative: {v}")))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Err(E::custom(format!(
+            "amount {v} is not an integer or too large for a JSON number, use a decimal string"
+        )))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        T::from_dec_str(v.trim()).map_err(E::custom)
+    }
+}
+
+/// Deserializes an unsigned amount
+pub(crate) fn deserialize_u256<'de, D>(deserializer: D) -> Result<U256, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(DecimalVisitor(PhantomData))
+}
+
+/// Deserializes an optional `u8`, like `dec`
+pub(crate) fn deserialize_opt_u8<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = deserialize_u256(deserializer)?;
+    if value > U256::from(u8::MAX) {
+        return Err(de::Error::custom(format!("value out of range: {value}")));
+    }
+    Ok(Some(value.as_u32() as u8))
+}
+
+/// (De)serializes a signed amount as decimal string
+pub(crate) mod signed {
+    use super::*;
+
+    pub(crate) fn serialize<S>(amount: &I256, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(amount)
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<I256, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DecimalVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_amounts() {
+        assert_eq!(format_units(U256::from(1000u64), 0), "1000");
+        assert_eq!(format_units(U256::from(1500u64), 3), "1.5");
+        assert_eq!(format_units(U256::from(15u64), 3), "0.015");
+        assert_eq!(format_units(U256::from(2000u64), 3), "2");
+        assert_eq!(
+            format_units(U256::from_dec_str("21000000000000000000000000").unwrap(), 18),
+            "21000000"
+        );
+    }
+
+    #[test]
+    fn parse_amounts() {
+        assert_eq!(U256::from_dec_str("1000").unwrap(), U256::from(1000u64));
+        assert!(matches!(
+            <U256 as Decimal>::from_dec_str(&"9".repeat(80)),
+            Err(InscriptionError::AmountOverflow(_))
+        ));
+        assert!(matches!(
+            <U256 as Decimal>::from_dec_str("-1"),
+            Err(InscriptionError::InvalidAmount(_))
+        ));
+        assert!(matches!(
+            <I256 as Decimal>::from_dec_str(&format!("-{}", "9".repeat(80))),
+            Err(InscriptionError::AmountOverflow(_))
+        ));
+        assert_eq!(<I256 as Decimal>::from_dec_str("-1").unwrap(), I256::from(-1));
+    }
+
+    #[test]
+    fn decode_amounts() {
+        use serde::de::value::{Error, F64Deserializer, I128Deserializer, U128Deserializer};
+
+        assert_eq!(
+            deserialize_u256(U128Deserializer::<Error>::new(u128::MAX)).unwrap(),
+            U256::from(u128::MAX)
+        );
+        assert!(deserialize_u256(I128Deserializer::<Error>::new(-1)).is_err());
+        assert_eq!(
+            signed::deserialize(I128Deserializer::<Error>::new(i128::MIN)).unwrap(),
+            I256::from(i128::MIN)
+        );
+        assert!(deserialize_u256(F64Deserializer::<Error>::new(2.1e25)).is_err());
+
+        let amount: serde_json::Value = serde_json::from_str("21000000000000000000000000").unwrap();
+        let err = deserialize_u256(amount.clone()).unwrap_err();
+        assert!(err.to_string().contains("use a decimal string"), "{err}");
+        assert!(matches!(unsigned_amount_error(&amount), Some(InscriptionError::InvalidAmount(_))));
+
+        assert!(unsigned_amount_error(&Value::from(1000)).is_none());
+        assert!(unsigned_amount_error(&Value::from("1000")).is_none());
+        assert!(matches!(
+            unsigned_amount_error(&Value::from(-1)),
+            Some(InscriptionError::InvalidAmount(_))
+        ));
+        assert!(signed_amount_error(&Value::from(-1)).is_none());
+        assert!(matches!(
+            signed_amount_error(&Value::from("9".repeat(80))),
+            Some(InscriptionError::AmountOverflow(_))
+        ));
+    }
+}
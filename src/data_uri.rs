@@ -0,0 +1,240 @@
+//! RFC 2397 `data:` URIs
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::{fmt, str::FromStr};
+
+/// The scheme of a data URI
+const SCHEME: &str = "data:";
+
+/// A `data:` URI as described in [RFC 2397](https://www.rfc-editor.org/rfc/rfc2397)
+///
+/// `data:[<mediatype>][;base64],<data>`
+///
+/// Note: non base64 data is kept verbatim and _not_ percent-decoded, because inscriptions embed
+/// plain JSON in the URI.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DataUri {
+    /// The mime type, e.g. `application/json`, if any
+    pub mime_type: Option<String>,
+    /// The parameters of the media type, e.g. `charset=utf-8`
+    pub parameters: Vec<(String, String)>,
+    /// Whether the data is base64 encoded
+    pub base64: bool,
+    /// The decoded payload
+    pub data: Vec<u8>,
+}
+
+impl DataUri {
+    /// Creates a new data URI without mime type for the given payload, `data:,<data>`
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        Self { data: data.into(), ..Default::default() }
+    }
+
+    /// Sets the mime type
+    pub fn with_mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    /// Adds a media type parameter
+    pub fn with_parameter(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.parameters.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets whether the payload should be base64 encoded
+    pub const fn with_base64(mut self, base64: bool) -> Self {
+        self.base64 = base64;
+        self
+    }
+
+    /// Replaces the payload
+    pub fn with_data(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.data = data.into();
+        self
+    }
+
+    /// Returns the media type, which defaults to `text/plain` if none is set.
+    pub fn media_type(&self) -> &str {
+        self.mime_type.as_deref().unwrap_or("text/plain")
+    }
+
+    /// Returns the value of the given parameter, if set.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns everything up to and including the `,` that separates the payload.
+    pub fn header(&self) -> String {
+        let mut header = SCHEME.to_string();
+        if let Some(mime_type) = &self.mime_type {
+            header.push_str(mime_type);
+        }
+        for (name, value) in &self.parameters {
+            header.push(';');
+            header.push_str(name);
+            header.push('=');
+            header.push_str(value);
+        }
+        if self.base64 {
+            header.push_str(";base64");
+        }
+        header.push(',');
+        header
+    }
+
+    /// Encodes the data URI
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = self.header().into_bytes();
+        if self.base64 {
+            buf.extend_from_slice(STANDARD.encode(&self.data).as_bytes());
+        } else {
+            buf.extend_from_slice(&self.data);
+        }
+        buf
+    }
+
+    /// Returns true if the input starts with the `data:` scheme, in any case
+    pub fn has_scheme(input: &[u8]) -> bool {
+        input
+            .get(..SCHEME.len())
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case(SCHEME.as_bytes()))
+    }
+
+    /// Decodes a data URI
+    pub fn parse(input: &[u8]) -> Result<Self, DataUriError> {
+        if !Self::has_scheme(input) {
+            return Err(DataUriError::MissingScheme);
+        }
+        let input = &input[SCHEME.len()..];
+        let comma = input.iter().position(|b| *b == b',').ok_or(DataUriError::MissingComma)?;
+        let header = std::str::from_utf8(&input[..comma]).map_err(|_| DataUriError::InvalidUtf8)?;
+        let payload = &input[comma + 1..];
+
+        let mut uri = DataUri::default();
+        let mut segments = header.split(';').peekable();
+        if let Some(mime_type) = segments.next_if(|s| !s.contains('=')) {
+            if mime_type.eq_ignore_ascii_case("base64") {
+                uri.base64 = true;
+            } else if !mime_type.is_empty() {
+                uri.mime_type = Some(mime_type.to_string());
+            }
+        }
+        for segment in segments {
+            if uri.base64 {
+                // `;base64` must be the last segment
+                return Err(DataUriError::InvalidParameter(segment.to_string()));
+            }
+            if segment.eq_ignore_ascii_case("base64") {
+                uri.base64 = true;
+            } else if let Some((name, value)) = segment.split_once('=') {
+                uri.parameters.push((name.to_string(), value.to_string()));
+            } else {
+                return Err(DataUriError::InvalidParameter(segment.to_string()));
+            }
+        }
+
+        uri.data = if uri.base64 {
+            STANDARD.decode(payload).map_err(|_| DataUriError::InvalidBase64)?
+        } else {
+            payload.to_vec()
+        };
+
+        Ok(uri)
+    }
+}
+
+impl fmt::Display for DataUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        String::from_utf8_lossy(&self.encode()).fmt(f)
+    }
+}
+
+impl FromStr for DataUri {
+    type Err = DataUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s.as_bytes())
+    }
+}
+
+/// Errors that can occur when decoding a [DataUri]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DataUriError {
+    /// The input does not start with `data:`
+    MissingScheme,
+    /// The header is not terminated by a `,`
+    MissingComma,
+    /// The header is not valid utf-8
+    InvalidUtf8,
+    /// A header segment is not a `name=value` parameter
+    InvalidParameter(String),
+    /// The payload is not valid base64
+    InvalidBase64,
+}
+
+impl fmt::Display for DataUriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataUriError::MissingScheme => write!(f, "missing `{SCHEME}` prefix"),
+            DataUriError::MissingComma => write!(f, "missing `,` after data URI header"),
+            DataUriError::InvalidUtf8 => write!(f, "data URI header is not valid utf-8"),
+            DataUriError::InvalidParameter(param) => {
+                write!(f, "invalid data URI parameter: {param}")
+            }
+            DataUriError::InvalidBase64 => write!(f, "data URI payload is not valid base64"),
+        }
+    }
+}
+
+impl std::error::Error for DataUriError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_data_uris() {
+        let uri = DataUri::parse(br#"data:,{"p":"erc-20"}"#).unwrap();
+        assert_eq!(uri, DataUri::new(r#"{"p":"erc-20"}"#));
+        assert_eq!(uri.media_type(), "text/plain");
+
+        let uri = DataUri::parse(br#"data:application/json,{"p":"erc-20"}"#).unwrap();
+        assert_eq!(uri.mime_type.as_deref(), Some("application/json"));
+        assert_eq!(uri.data, br#"{"p":"erc-20"}"#);
+
+        let uri = DataUri::parse(b"data:text/plain;charset=utf-8,hello, world").unwrap();
+        assert_eq!(uri.parameter("charset"), Some("utf-8"));
+        assert_eq!(uri.data, b"hello, world");
+
+        let uri = DataUri::parse(b"data:image/png;base64,iVBORw0KGgo=").unwrap();
+        assert!(uri.base64);
+        assert_eq!(uri.data, b"\x89PNG\r\n\x1a\n");
+
+        let uri = DataUri::parse(b"data:;base64,aGk=").unwrap();
+        assert_eq!(uri, DataUri::new("hi").with_base64(true));
+
+        assert_eq!(DataUri::parse(b"{}"), Err(DataUriError::MissingScheme));
+        assert_eq!(DataUri::parse(b"data:application/json"), Err(DataUriError::MissingComma));
+        assert_eq!(DataUri::parse(b"data:;base64,!!"), Err(DataUriError::InvalidBase64));
+    }
+
+    #[test]
+    fn encode_data_uris() {
+        for uri in [
+            r#"data:,{"p":"erc-20"}"#,
+            r#"data:application/json,{"p":"erc-20"}"#,
+            "data:text/plain;charset=utf-8,hello",
+            "data:image/png;base64,iVBORw0KGgo=",
+        ] {
+            assert_eq!(DataUri::from_str(uri).unwrap().to_string(), uri);
+        }
+
+        let uri =
+            DataUri::new("hi").with_mime_type("text/plain").with_parameter("charset", "utf-8");
+        assert_eq!(uri.header(), "data:text/plain;charset=utf-8,");
+    }
+}
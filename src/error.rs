@@ -0,0 +1,98 @@
+//! Inscription errors
+
+use crate::{DataUriError, Op, RuleViolation};
+use std::{convert::Infallible, fmt, str::Utf8Error};
+
+/// Errors that can occur when decoding or validating an inscription
+#[derive(Debug)]
+pub enum InscriptionError {
+    /// The calldata is not a valid data URI, e.g. it lacks the `data:` prefix
+    InvalidDataUri(DataUriError),
+    /// The calldata starts with `0x` but is not valid hex
+    InvalidHex(String),
+    /// The payload is not valid utf-8
+    InvalidUtf8(Utf8Error),
+    /// The payload is not valid JSON or does not match the inscription
+    Json(serde_json::Error),
+    /// The inscription has a different operation than expected
+    WrongOp {
+        /// The expected operation
+        expected: Op,
+        /// The operation of the inscription, if known
+        found: Option<Op>,
+    },
+    /// The tick is invalid, e.g. empty
+    InvalidTick(String),
+    /// An amount is not a decimal integer
+    InvalidAmount(String),
+    /// An amount does not fit into 256 bits
+    AmountOverflow(String),
+    /// The inscription violates the rules of its protocol
+    Rule(RuleViolation),
+    /// A template has an unknown or malformed placeholder
+    InvalidTemplate(String),
+}
+
+impl fmt::Display for InscriptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InscriptionError::InvalidDataUri(err) => err.fmt(f),
+            InscriptionError::InvalidHex(err) => write!(f, "invalid hex calldata: {err}"),
+            InscriptionError::InvalidUtf8(err) => write!(f, "payload is not valid utf-8: {err}"),
+            InscriptionError::Json(err) => err.fmt(f),
+            InscriptionError::WrongOp { expected, found: Some(found) } => {
+                write!(f, "invalid operation: {found}, expected {expected}")
+            }
+            InscriptionError::WrongOp { expected, found: None } => {
+                write!(f, "invalid operation, expected {expected}")
+            }
+            InscriptionError::InvalidTick(tick) => write!(f, "invalid tick: {tick:?}"),
+            InscriptionError::InvalidAmount(amount) => write!(f, "invalid amount: {amount:?}"),
+            InscriptionError::AmountOverflow(amount) => write!(f, "amount overflow: {amount}"),
+            InscriptionError::Rule(err) => err.fmt(f),
+            InscriptionError::InvalidTemplate(err) => write!(f, "invalid template: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for InscriptionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InscriptionError::InvalidDataUri(err) => Some(err),
+            InscriptionError::InvalidUtf8(err) => Some(err),
+            InscriptionError::Json(err) => Some(err),
+            InscriptionError::Rule(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<Infallible> for InscriptionError {
+    fn from(err: Infallible) -> Self {
+        match err {}
+    }
+}
+
+impl From<DataUriError> for InscriptionError {
+    fn from(err: DataUriError) -> Self {
+        InscriptionError::InvalidDataUri(err)
+    }
+}
+
+impl From<Utf8Error> for InscriptionError {
+    fn from(err: Utf8Error) -> Self {
+        InscriptionError::InvalidUtf8(err)
+    }
+}
+
+impl From<serde_json::Error> for InscriptionError {
+    fn from(err: serde_json::Error) -> Self {
+        InscriptionError::Json(err)
+    }
+}
+
+impl From<RuleViolation> for InscriptionError {
+    fn from(err: RuleViolation) -> Self {
+        InscriptionError::Rule(err)
+    }
+}
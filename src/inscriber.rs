@@ -0,0 +1,2042 @@
+//! Sends an inscription in many transactions and tracks them until they're confirmed.
+//!
+//! ```no_run
+//! # async fn mint<M>(provider: M) -> Result<(), Box<dyn std::error::Error>>
+//! # where
+//! #     M: ethers::providers::Middleware + Clone + Unpin + 'static,
+//! # {
+//! use ethers::types::Bytes;
+//! use futures::StreamExt;
+//! use inscribememaybe::{
+//!     inscriber::{Inscriber, InscriptionEvent},
+//!     IdTemplate, InscriptionCalldata, Mint,
+//! };
+//!
+//! let mint: Mint =
+//!     serde_json::from_str(r#"{"p":"fair-20","op":"mint","tick":"brr","amt":"1000"}"#)?;
+//! let id: IdTemplate = "{uuid}".parse()?;
+//! // every transaction gets a unique mint id
+//! let calldata = move |n: u64, nonce: u64| {
+//!     let mut mint = mint.clone();
+//!     mint.id = Some(id.render(n, nonce));
+//!     Bytes::from(mint.calldata())
+//! };
+//! let mut inscriber = Inscriber::builder(provider, calldata).max_transactions(10).build().await?;
+//! let cancel = inscriber.cancel_handle();
+//! while let Some(event) = inscriber.next().await {
+//!     if let InscriptionEvent::Failed { .. } = event? {
+//!         // stop sending new transactions, but keep tracking the ones already sent
+//!         cancel.cancel();
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use ethers::{
+    providers::{Middleware, MiddlewareError},
+    types::{
+        transaction::eip2718::TypedTransaction, Address, Bytes, Eip1559TransactionRequest,
+        TransactionReceipt, TransactionRequest, TxHash, U256,
+    },
+    utils::keccak256,
+};
+use futures::{
+    future::BoxFuture, stream::FuturesUnordered, task::AtomicWaker, Future, Stream, StreamExt,
+};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{ready, Context, Poll},
+    time::{Duration, Instant},
+};
+use tracing::{debug, warn};
+
+/// The gas added on top of the intrinsic gas if no gas limit is set, in percent
+pub const DEFAULT_GAS_MARGIN: u64 = 10;
+
+/// Returns the intrinsic gas of a transaction with the given calldata.
+///
+/// This is the base cost of a transaction plus 4 gas for every zero and 16 gas for every non-zero
+/// calldata byte.
+pub fn intrinsic_gas(calldata: &[u8]) -> u64 {
+    const TX_GAS: u64 = 21000;
+    const TX_DATA_ZERO_GAS: u64 = 4;
+    const TX_DATA_NON_ZERO_GAS: u64 = 16;
+
+    let zeros = calldata.iter().filter(|b| **b == 0).count() as u64;
+    let non_zeros = calldata.len() as u64 - zeros;
+    TX_GAS + zeros * TX_DATA_ZERO_GAS + non_zeros * TX_DATA_NON_ZERO_GAS
+}
+
+/// Returns the default gas limit for the calldata, the intrinsic gas plus [DEFAULT_GAS_MARGIN]
+fn default_gas_limit(calldata: &[u8]) -> u64 {
+    let intrinsic = intrinsic_gas(calldata);
+    intrinsic + intrinsic * DEFAULT_GAS_MARGIN / 100
+}
+
+/// Produces the calldata of every transaction an [Inscriber] sends
+///
+/// This is implemented for [Bytes], which sends the same calldata in every transaction, and for
+/// closures `FnMut(n, nonce) -> Bytes`.
+pub trait CalldataSource: Send {
+    /// Returns the calldata of the `n`th transaction, starting at 0, which is first sent with the
+    /// given nonce
+    fn next_calldata(&mut self, n: u64, nonce: u64) -> Bytes;
+}
+
+impl CalldataSource for Bytes {
+    fn next_calldata(&mut self, _n: u64, _nonce: u64) -> Bytes {
+        self.clone()
+    }
+}
+
+impl<F> CalldataSource for F
+where
+    F: FnMut(u64, u64) -> Bytes + Send,
+{
+    fn next_calldata(&mut self, n: u64, nonce: u64) -> Bytes {
+        self(n, nonce)
+    }
+}
+
+/// A transaction signed by an [Inscriber], see [Journal]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedTransaction {
+    /// The index of the transaction, starting at 0, kept by replacements and resends
+    pub n: u64,
+    /// The nonce of the transaction
+    pub nonce: u64,
+    /// The hash of the transaction
+    pub hash: TxHash,
+    /// The signed transaction as sent to the node
+    pub raw: Bytes,
+}
+
+/// An error of the provider or a [Journal]
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Records every transaction an [Inscriber] signs before it's broadcast
+///
+/// This allows resuming the transactions of an interrupted process, see
+/// [InscriberBuilder::resume].
+pub trait Journal: Send + Sync {
+    /// Records the signed transaction, it is broadcast once the returned future resolved.
+    ///
+    /// An error is treated as a transient error of sending the transaction.
+    fn record(&self, tx: SignedTransaction) -> BoxFuture<'_, Result<(), BoxError>>;
+}
+
+/// The fees of a transaction, in wei
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fees {
+    /// A legacy transaction
+    Legacy {
+        /// The gas price
+        gas_price: U256,
+    },
+    /// An EIP-1559 transaction
+    Eip1559 {
+        /// The max fee per gas
+        max_fee: U256,
+        /// The max priority fee per gas
+        priority_fee: U256,
+    },
+}
+
+impl Fees {
+    /// Returns the maximum fee per gas the transaction can pay.
+    pub const fn max_fee(&self) -> U256 {
+        match self {
+            Fees::Legacy { gas_price } => *gas_price,
+            Fees::Eip1559 { max_fee, .. } => *max_fee,
+        }
+    }
+
+    /// Raises the fees of the transaction to these fees, if they are higher.
+    fn raise(&self, tx: &mut TypedTransaction) {
+        match (tx, self) {
+            (TypedTransaction::Legacy(tx), Fees::Legacy { gas_price }) => {
+                tx.gas_price = tx.gas_price.max(Some(*gas_price));
+            }
+            (TypedTransaction::Eip2930(tx), Fees::Legacy { gas_price }) => {
+                tx.tx.gas_price = tx.tx.gas_price.max(Some(*gas_price));
+            }
+            (TypedTransaction::Eip1559(tx), Fees::Eip1559 { max_fee, priority_fee }) => {
+                tx.max_fee_per_gas = tx.max_fee_per_gas.max(Some(*max_fee));
+                tx.max_priority_fee_per_gas = tx.max_priority_fee_per_gas.max(Some(*priority_fee));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the maximum fee per gas the transaction can pay, if set.
+const fn tx_max_fee(tx: &TypedTransaction) -> Option<U256> {
+    match tx {
+        TypedTransaction::Legacy(tx) => tx.gas_price,
+        TypedTransaction::Eip2930(tx) => tx.tx.gas_price,
+        TypedTransaction::Eip1559(tx) => tx.max_fee_per_gas,
+    }
+}
+
+/// Replaces transactions that are not mined in time with higher fees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Replacement {
+    /// How long to wait for a transaction before replacing it
+    timeout: Duration,
+    /// The percentage to bump the fees by
+    bump: u64,
+    /// The maximum number of replacements per nonce
+    max_replacements: usize,
+    /// The fee per gas a replacement must not exceed
+    fee_cap: Option<U256>,
+}
+
+impl Replacement {
+    /// Replaces transactions that are not mined after the timeout, bumping the fees by 10% up to 5
+    /// times.
+    pub const fn new(timeout: Duration) -> Self {
+        Self { timeout, bump: 10, max_replacements: 5, fee_cap: None }
+    }
+
+    /// Sets the percentage to bump the fees by, most nodes require at least 10%
+    pub const fn with_bump(mut self, bump: u64) -> Self {
+        self.bump = bump;
+        self
+    }
+
+    /// Sets the maximum number of replacements per nonce
+    pub const fn with_max_replacements(mut self, max_replacements: usize) -> Self {
+        self.max_replacements = max_replacements;
+        self
+    }
+
+    /// Sets the fee per gas a replacement must not exceed
+    pub const fn with_fee_cap(mut self, fee_cap: U256) -> Self {
+        self.fee_cap = Some(fee_cap);
+        self
+    }
+
+    /// Returns the transaction with bumped fees, or `None` if the fees would exceed the cap
+    pub fn bump(&self, tx: &TypedTransaction) -> Option<TypedTransaction> {
+        let bump = |fee: U256| (fee * U256::from(100 + self.bump) + 99) / 100;
+        let mut tx = tx.clone();
+        let max_fee = match &mut tx {
+            TypedTransaction::Legacy(tx) => *tx.gas_price.insert(bump(tx.gas_price?)),
+            TypedTransaction::Eip2930(tx) => *tx.tx.gas_price.insert(bump(tx.tx.gas_price?)),
+            TypedTransaction::Eip1559(tx) => {
+                tx.max_priority_fee_per_gas = tx.max_priority_fee_per_gas.map(bump);
+                *tx.max_fee_per_gas.insert(bump(tx.max_fee_per_gas?))
+            }
+        };
+        if self.fee_cap.is_some_and(|cap| max_fee > cap) {
+            return None;
+        }
+        Some(tx)
+    }
+}
+
+/// Stops an [Inscriber] from sending new transactions.
+///
+/// Transactions that were already sent are still tracked until they're confirmed, drop the
+/// [Inscriber] to stop tracking them as well.
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    inner: Arc<CancelState>,
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    waker: AtomicWaker,
+}
+
+impl CancelHandle {
+    /// Stops sending new transactions
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Relaxed);
+        self.inner.waker.wake();
+    }
+
+    /// Returns true if the [Inscriber] was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Registers the task to wake up on cancellation and returns true if it was cancelled
+    fn poll_cancelled(&self, cx: &Context<'_>) -> bool {
+        self.inner.waker.register(cx.waker());
+        self.is_cancelled()
+    }
+}
+
+/// Errors that end an [Inscriber]
+#[derive(Debug)]
+pub enum InscriberError {
+    /// The provider has no default sender and none was set
+    MissingSender,
+    /// Failed to fetch a value that was not set from the provider
+    Provider(BoxError),
+    /// The node rejected the transaction and resending it can't succeed, e.g. due to insufficient
+    /// funds
+    Rejected {
+        /// The nonce of the transaction
+        nonce: u64,
+        /// The error returned by the node
+        source: BoxError,
+    },
+    /// The transaction still failed after the maximum number of retries
+    RetriesExhausted {
+        /// The nonce of the transaction
+        nonce: u64,
+        /// How often the transaction was resent
+        retries: u32,
+        /// The last error
+        source: BoxError,
+    },
+    /// The fees exceed the fee cap and are no longer updated, so no more transactions can be sent
+    FeeCapExceeded {
+        /// The max fee per gas of new transactions
+        max_fee: U256,
+        /// The fee cap
+        fee_cap: U256,
+    },
+}
+
+impl fmt::Display for InscriberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InscriberError::MissingSender => f.write_str("no sender set"),
+            InscriberError::Provider(err) => err.fmt(f),
+            InscriberError::Rejected { nonce, source } => {
+                write!(f, "failed to mint with nonce {nonce}: {source}")
+            }
+            InscriberError::RetriesExhausted { nonce, retries, source } => {
+                write!(f, "failed to mint with nonce {nonce} after {retries} retries: {source}")
+            }
+            InscriberError::FeeCapExceeded { max_fee, fee_cap } => {
+                write!(f, "max fee per gas {max_fee} exceeds the fee cap of {fee_cap}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InscriberError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InscriberError::MissingSender | InscriberError::FeeCapExceeded { .. } => None,
+            InscriberError::Provider(err)
+            | InscriberError::Rejected { source: err, .. }
+            | InscriberError::RetriesExhausted { source: err, .. } => Some(&**err),
+        }
+    }
+}
+
+/// Events of an [Inscriber]
+#[derive(Debug, Clone)]
+pub enum InscriptionEvent {
+    /// A transaction was sent
+    Sent {
+        /// The nonce of the transaction
+        nonce: u64,
+        /// The hash of the transaction
+        hash: TxHash,
+    },
+    /// A transaction was mined successfully and has the configured number of confirmations
+    Confirmed {
+        /// The nonce of the transaction
+        nonce: u64,
+        /// The receipt of the transaction
+        receipt: TransactionReceipt,
+        /// The sender of the transaction
+        sender: Address,
+        /// The chain the transaction was sent to
+        chain_id: u64,
+        /// The calldata of the transaction
+        calldata: Bytes,
+        /// The original transaction, if the mined transaction replaced it
+        replaces: Option<TxHash>,
+    },
+    /// A transaction was mined but reverted
+    Failed {
+        /// The nonce of the transaction
+        nonce: u64,
+        /// The receipt of the transaction
+        receipt: TransactionReceipt,
+        /// The sender of the transaction
+        sender: Address,
+        /// The chain the transaction was sent to
+        chain_id: u64,
+        /// The calldata of the transaction
+        calldata: Bytes,
+        /// The original transaction, if the mined transaction replaced it
+        replaces: Option<TxHash>,
+    },
+    /// A transaction was dropped from the mempool and is resent
+    Dropped {
+        /// The nonce of the transaction
+        nonce: u64,
+        /// The hash of the transaction
+        hash: TxHash,
+    },
+    /// The block of a transaction was reorged out before it was confirmed, the transaction is
+    /// resent
+    Reorged {
+        /// The nonce of the transaction
+        nonce: u64,
+        /// The hash of the transaction
+        hash: TxHash,
+        /// The block the transaction was included in
+        block: u64,
+    },
+    /// A transaction was not mined in time and a replacement with higher fees was sent.
+    ///
+    /// All transactions with the nonce are watched until one of them is mined.
+    Replaced {
+        /// The nonce of the transaction
+        nonce: u64,
+        /// The hash of the first transaction with the nonce
+        original: TxHash,
+        /// The hash of the transaction that is replaced
+        replaced: TxHash,
+        /// The hash of the replacement
+        hash: TxHash,
+        /// How long the replaced transaction was pending
+        elapsed: Duration,
+    },
+    /// New transactions are paused because a fee is above its threshold
+    Paused {
+        /// The fee that is above its threshold
+        reason: PauseReason,
+    },
+    /// The fee that paused new transactions dropped below its threshold.
+    ///
+    /// New transactions are sent again once no fee is above its threshold.
+    Resumed {
+        /// The fee that dropped below its threshold
+        reason: PauseReason,
+    },
+}
+
+/// The fee that pauses new transactions, see [InscriptionEvent::Paused]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseReason {
+    /// The base fee of the latest block, see [InscriberBuilder::max_base_fee]
+    BaseFee {
+        /// The base fee of the latest block
+        base_fee: U256,
+    },
+    /// The max fee per gas of new transactions, see [InscriberBuilder::fee_cap]
+    FeeCap {
+        /// The max fee per gas or gas price of new transactions
+        max_fee: U256,
+    },
+}
+
+/// Builds an [Inscriber], see [Inscriber::builder]
+pub struct InscriberBuilder<M> {
+    provider: M,
+    calldata: Box<dyn CalldataSource>,
+    sender: Option<Address>,
+    nonce: Option<u64>,
+    chain_id: Option<u64>,
+    gas: Option<u64>,
+    fees: Option<Fees>,
+    fee_updates: Option<Pin<Box<dyn Stream<Item = Fees> + Send>>>,
+    fee_cap: Option<U256>,
+    replacement: Option<Replacement>,
+    base_fee: Option<BaseFeeWatcher>,
+    journal: Option<Arc<dyn Journal>>,
+    resumed: Vec<ResumedTransaction>,
+    sent: u64,
+    max_transactions: u64,
+    concurrency: usize,
+    max_retries: u32,
+    confirmations: u64,
+}
+
+impl<M> InscriberBuilder<M> {
+    /// Sets the sender, defaults to the default sender of the provider
+    pub const fn sender(mut self, sender: Address) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    /// Sets the nonce of the first transaction, defaults to the transaction count of the sender
+    pub const fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the chain id, defaults to the chain id of the provider
+    pub const fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Sets the gas limit of every transaction, defaults to the intrinsic gas of each
+    /// transaction's calldata plus 10%
+    pub const fn gas(mut self, gas: u64) -> Self {
+        self.gas = Some(gas);
+        self
+    }
+
+    /// Sets the fees of every transaction, estimated by the provider if not set
+    pub const fn fees(mut self, fees: Fees) -> Self {
+        self.fees = Some(fees);
+        self
+    }
+
+    /// Updates the fees with every item of the stream, e.g. fees that are re-estimated every block.
+    ///
+    /// New transactions use the latest fees. Resent transactions and replacements are raised to
+    /// the latest fees if these are higher.
+    pub fn fee_updates(mut self, fees: impl Stream<Item = Fees> + Send + 'static) -> Self {
+        self.fee_updates = Some(Box::pin(fees));
+        self
+    }
+
+    /// Sets the max fee per gas no transaction is sent with.
+    ///
+    /// New transactions are paused while the fees exceed the cap, until a
+    /// [fee update](Self::fee_updates) drops below it. Replacements that would exceed the cap are
+    /// not sent.
+    pub const fn fee_cap(mut self, fee_cap: U256) -> Self {
+        self.fee_cap = Some(fee_cap);
+        self
+    }
+
+    /// Replaces transactions that are not mined in time with higher fees
+    pub const fn replacement(mut self, replacement: Replacement) -> Self {
+        self.replacement = Some(replacement);
+        self
+    }
+
+    /// Pauses sending new transactions while the base fee is above `max_base_fee`.
+    ///
+    /// `base_fees` yields the base fee of every new block. No transactions are sent until the first
+    /// base fee is received, so this must not be used on chains without base fees. If the stream
+    /// ends, new transactions are no longer paused.
+    pub fn max_base_fee(
+        mut self,
+        max_base_fee: U256,
+        base_fees: impl Stream<Item = U256> + Send + 'static,
+    ) -> Self {
+        self.base_fee =
+            Some(BaseFeeWatcher { base_fees: Box::pin(base_fees), max_base_fee, paused: None });
+        self
+    }
+
+    /// Records every signed transaction in the journal before it's broadcast
+    pub fn journal(mut self, journal: impl Journal + 'static) -> Self {
+        self.journal = Some(Arc::new(journal));
+        self
+    }
+
+    /// Tracks a transaction of an interrupted process that was sent but not mined yet.
+    ///
+    /// `n` is the index of the transaction and `hashes` are all transactions sent with its nonce,
+    /// the original first. The last one is resent if it was dropped, its nonce is not reused for
+    /// new transactions.
+    pub fn resume(mut self, n: u64, tx: TypedTransaction, hashes: Vec<TxHash>) -> Self {
+        self.resumed.push(ResumedTransaction { n, tx, hashes });
+        self
+    }
+
+    /// Sets the number of transactions that were already sent by an interrupted process, defaults
+    /// to 0
+    ///
+    /// These count towards [max_transactions](Self::max_transactions), new transactions continue
+    /// at this index.
+    pub const fn sent(mut self, sent: u64) -> Self {
+        self.sent = sent;
+        self
+    }
+
+    /// Sets the number of transactions to send, defaults to 1
+    pub const fn max_transactions(mut self, max_transactions: u64) -> Self {
+        self.max_transactions = max_transactions;
+        self
+    }
+
+    /// Sets the number of transactions to send concurrently, defaults to 16
+    pub const fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Sets how often sending a transaction may fail in a row before giving up, defaults to 5
+    ///
+    /// Failures are counted again from zero once the transaction was sent.
+    pub const fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the number of blocks a transaction must be included for to be confirmed, defaults to 1
+    ///
+    /// Mined transactions are checked on every new block until they're confirmed, these don't
+    /// count towards the [concurrency](Self::concurrency).
+    pub const fn confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
+}
+
+impl<M> InscriberBuilder<M>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    /// Builds the [Inscriber], fetching the values that are not set from the provider
+    pub async fn build(self) -> Result<Inscriber<M>, InscriberError> {
+        let provider_err = |err: M::Error| InscriberError::Provider(err.into());
+        let sender = self
+            .sender
+            .or_else(|| self.provider.default_sender())
+            .ok_or(InscriberError::MissingSender)?;
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => self
+                .provider
+                .get_transaction_count(sender, None)
+                .await
+                .map_err(provider_err)?
+                .as_u64(),
+        };
+        let chain_id = match self.chain_id {
+            Some(chain_id) => chain_id,
+            None => self.provider.get_chainid().await.map_err(provider_err)?.as_u64(),
+        };
+        let fees = match self.fees {
+            Some(fees) => fees,
+            None => match self.provider.estimate_eip1559_fees(None).await {
+                Ok((max_fee, priority_fee)) => Fees::Eip1559 { max_fee, priority_fee },
+                Err(err) => {
+                    debug!(%err, "failed to estimate eip1559 fees; using gas price");
+                    let gas_price = self.provider.get_gas_price().await.map_err(provider_err)?;
+                    Fees::Legacy { gas_price }
+                }
+            },
+        };
+
+        Ok(Inscriber {
+            pending: Default::default(),
+            confirming: Vec::new(),
+            confirmation_check: None,
+            checked_block: 0,
+            confirmed: VecDeque::new(),
+            calldata: self.calldata,
+            gas: self.gas,
+            fees,
+            fee_updates: self.fee_updates,
+            fee_cap: self.fee_cap,
+            fee_cap_paused: false,
+            base_fee: self.base_fee,
+            replacement: self.replacement,
+            journal: self.journal,
+            reserved_nonces: self.resumed.iter().filter_map(|resumed| resumed.nonce()).collect(),
+            resumed: self.resumed,
+            sender,
+            count: self.sent,
+            highest_nonce: nonce,
+            max_transactions: self.max_transactions,
+            max_retries: self.max_retries,
+            confirmations: self.confirmations,
+            concurrency: self.concurrency,
+            chain_id,
+            cancel: CancelHandle::default(),
+            failure: None,
+            provider: self.provider,
+        })
+    }
+}
+
+impl<M> fmt::Debug for InscriberBuilder<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InscriberBuilder")
+            .field("sender", &self.sender)
+            .field("nonce", &self.nonce)
+            .field("chain_id", &self.chain_id)
+            .field("gas", &self.gas)
+            .field("fees", &self.fees)
+            .field("fee_cap", &self.fee_cap)
+            .field("replacement", &self.replacement)
+            .field("base_fee", &self.base_fee)
+            .field("resumed", &self.resumed)
+            .field("sent", &self.sent)
+            .field("max_transactions", &self.max_transactions)
+            .field("concurrency", &self.concurrency)
+            .field("max_retries", &self.max_retries)
+            .field("confirmations", &self.confirmations)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sends calldata in many transactions to the sender itself.
+///
+/// This is a [Stream] of [InscriptionEvent]s that ends once all transactions are confirmed, or with
+/// an [InscriberError] if a transaction can't be sent. After an error no new transactions are
+/// sent, but the error is only returned once the transactions already sent are mined.
+///
+/// Caution: the provider must sign the transactions, e.g. a
+/// [SignerMiddleware](ethers::middleware::SignerMiddleware).
+#[must_use = "streams do nothing unless polled"]
+pub struct Inscriber<M> {
+    /// in progress transactions
+    pending: FuturesUnordered<Pin<Box<dyn Future<Output = InscriptionResult> + Send>>>,
+    /// Mined transactions that wait for their confirmations, these don't count towards the
+    /// concurrency
+    confirming: Vec<MinedTransaction>,
+    /// Fetches the receipts of `confirming` on the next block, if any are waiting
+    confirmation_check: Option<BoxFuture<'static, ReceiptCheck>>,
+    /// The block the receipts of `confirming` were last checked at
+    checked_block: u64,
+    /// Transactions that are confirmed or were reorged out, handled before `pending`
+    confirmed: VecDeque<InscriptionResult>,
+    /// The call data of every transaction
+    calldata: Box<dyn CalldataSource>,
+    /// The gas limit of every transaction, computed from the calldata if not set
+    gas: Option<u64>,
+    /// The fees of new transactions
+    fees: Fees,
+    /// Updates the fees, if set
+    fee_updates: Option<Pin<Box<dyn Stream<Item = Fees> + Send>>>,
+    /// The max fee per gas no transaction is sent with, if set
+    fee_cap: Option<U256>,
+    /// Whether new transactions are paused because the fees exceed the fee cap
+    fee_cap_paused: bool,
+    /// Pauses new transactions while the base fee is too high, if set
+    base_fee: Option<BaseFeeWatcher>,
+    /// Replaces transactions that are not mined in time, if set
+    replacement: Option<Replacement>,
+    /// Records signed transactions before they're broadcast, if set
+    journal: Option<Arc<dyn Journal>>,
+    /// Transactions of an interrupted process that are tracked on the first poll
+    resumed: Vec<ResumedTransaction>,
+    /// Nonces of resumed transactions that are skipped by new transactions
+    reserved_nonces: BTreeSet<u64>,
+    sender: Address,
+    /// how many transactions we sent already
+    count: u64,
+    /// The next nonce to use
+    highest_nonce: u64,
+    /// How many transactions to send
+    max_transactions: u64,
+    /// How often sending a transaction may fail in a row
+    max_retries: u32,
+    /// How many blocks a transaction must be included for
+    confirmations: u64,
+    /// How many transactions to send concurrently
+    concurrency: usize,
+    /// The targeted chain id
+    chain_id: u64,
+    /// Stops sending new transactions
+    cancel: CancelHandle,
+    /// The error that ends the stream once the pending transactions are done
+    failure: Option<InscriberError>,
+    /// The provider to use
+    ///
+    /// Caution: we expect this to sign the transaction
+    provider: M,
+}
+
+impl<M> Inscriber<M> {
+    /// Returns a builder that sends the calldata with the given provider
+    pub fn builder(provider: M, calldata: impl CalldataSource + 'static) -> InscriberBuilder<M> {
+        InscriberBuilder {
+            provider,
+            calldata: Box::new(calldata),
+            sender: None,
+            nonce: None,
+            chain_id: None,
+            gas: None,
+            fees: None,
+            fee_updates: None,
+            fee_cap: None,
+            replacement: None,
+            base_fee: None,
+            journal: None,
+            resumed: Vec::new(),
+            sent: 0,
+            max_transactions: 1,
+            concurrency: 16,
+            max_retries: 5,
+            confirmations: 1,
+        }
+    }
+
+    /// Returns a handle to stop sending new transactions
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Returns the sender of the transactions
+    pub const fn sender(&self) -> Address {
+        self.sender
+    }
+
+    /// Returns the chain the transactions are sent to
+    pub const fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Returns the nonce of the next new transaction
+    pub const fn next_nonce(&self) -> u64 {
+        self.highest_nonce
+    }
+
+    /// Returns the fees of new transactions
+    pub const fn fees(&self) -> Fees {
+        self.fees
+    }
+
+    /// Returns the gas limit of every transaction, if set
+    pub const fn gas(&self) -> Option<u64> {
+        self.gas
+    }
+
+    /// Uses the fees for new transactions.
+    fn update_fees(&mut self, fees: Fees) {
+        if fees != self.fees {
+            debug!(?fees, "updated fees");
+            self.fees = fees;
+        }
+    }
+
+    /// Returns true if the fees of new transactions exceed the fee cap.
+    fn exceeds_fee_cap(&self) -> bool {
+        self.fee_cap.is_some_and(|cap| self.fees.max_fee() > cap)
+    }
+
+    /// Raises the fees of the transaction to the fees of new transactions, unless these exceed
+    /// the fee cap.
+    fn raise_fees(&self, tx: &mut TypedTransaction) {
+        if !self.exceeds_fee_cap() {
+            self.fees.raise(tx);
+        }
+    }
+
+    /// Returns the transaction with its fees raised to the fees of new transactions and bumped by
+    /// the replacement bump, 10% by default, or `None` if the fees would exceed the fee cap.
+    fn bump_fees(&self, tx: &TypedTransaction) -> Option<TypedTransaction> {
+        let mut tx = tx.clone();
+        self.raise_fees(&mut tx);
+        let replacement = self.replacement.unwrap_or(Replacement::new(Duration::ZERO));
+        replacement.bump(&tx).filter(|tx| self.within_fee_cap(tx))
+    }
+
+    /// Returns an event if new transactions were paused or resumed because the fees crossed the
+    /// fee cap.
+    fn check_fee_cap(&mut self) -> Option<InscriptionEvent> {
+        let paused = self.exceeds_fee_cap();
+        if paused == self.fee_cap_paused {
+            return None;
+        }
+        self.fee_cap_paused = paused;
+        let reason = PauseReason::FeeCap { max_fee: self.fees.max_fee() };
+        Some(if paused {
+            InscriptionEvent::Paused { reason }
+        } else {
+            InscriptionEvent::Resumed { reason }
+        })
+    }
+
+    /// Returns true if the transaction's fees don't exceed the fee cap.
+    fn within_fee_cap(&self, tx: &TypedTransaction) -> bool {
+        match (self.fee_cap, tx_max_fee(tx)) {
+            (Some(cap), Some(max_fee)) => max_fee <= cap,
+            _ => true,
+        }
+    }
+
+    /// Moves the transactions waiting for confirmations that are confirmed at the block or whose
+    /// block was reorged out to `confirmed`.
+    ///
+    /// If a transaction was moved to another block by a reorg, it waits for the confirmations of
+    /// the new block instead.
+    fn update_confirmations(
+        &mut self,
+        block: u64,
+        receipts: Vec<(TxHash, Option<TransactionReceipt>)>,
+    ) {
+        self.checked_block = self.checked_block.max(block);
+        for (hash, current) in receipts {
+            let Some(i) =
+                self.confirming.iter().position(|mined| mined.receipt.transaction_hash == hash)
+            else {
+                continue;
+            };
+            let mined = &mut self.confirming[i];
+            if mined.block() >= block {
+                // the node may not know the receipt's block yet
+                continue;
+            }
+            let reorged = match current {
+                Some(current) if current.block_number.is_some() => {
+                    if current.block_hash != mined.receipt.block_hash {
+                        debug!(?hash, old_block=?mined.receipt.block_hash, new_block=?current.block_hash, "transaction moved by reorg");
+                    }
+                    mined.receipt = current;
+                    if block + 1 < mined.block() + self.confirmations {
+                        continue;
+                    }
+                    false
+                }
+                _ => true,
+            };
+            let MinedTransaction { n, tx, nonce, hashes, receipt } = self.confirming.swap_remove(i);
+            let res =
+                Ok(if reorged { TxStatus::Reorged(receipt) } else { TxStatus::Confirmed(receipt) });
+            let sent_at = Instant::now();
+            let result = InscriptionResult {
+                n,
+                tx,
+                nonce,
+                hashes,
+                retries: 0,
+                sent_at,
+                replacing: None,
+                res,
+            };
+            self.confirmed.push_back(result);
+        }
+    }
+
+    /// Returns the next transaction to send.
+    fn next_transaction(&mut self) -> TypedTransaction {
+        while self.reserved_nonces.remove(&self.highest_nonce) {
+            self.highest_nonce += 1;
+        }
+        let calldata = self.calldata.next_calldata(self.count, self.highest_nonce);
+        let gas = self.gas.unwrap_or_else(|| default_gas_limit(&calldata));
+        match self.fees {
+            Fees::Legacy { gas_price } => TransactionRequest::new()
+                .from(self.sender)
+                .to(self.sender)
+                .value(0u64)
+                .gas(gas)
+                .gas_price(gas_price)
+                .nonce(self.highest_nonce)
+                .chain_id(self.chain_id)
+                .data(calldata)
+                .into(),
+            Fees::Eip1559 { max_fee, priority_fee } => Eip1559TransactionRequest::new()
+                .from(self.sender)
+                .to(self.sender)
+                .value(0u64)
+                .gas(gas)
+                .max_fee_per_gas(max_fee)
+                .max_priority_fee_per_gas(priority_fee)
+                .nonce(self.highest_nonce)
+                .chain_id(self.chain_id)
+                .data(calldata)
+                .into(),
+        }
+    }
+}
+
+impl<M> Inscriber<M>
+where
+    M: Middleware + Clone + Unpin + 'static,
+{
+    /// This starts sending the given transaction
+    ///
+    /// `n` is the index of the transaction, `hashes` are the transactions that were already sent
+    /// with the same nonce, `retries` is the number of times sending it failed in a row.
+    /// `replacing` is the replaced transaction and how long it was pending, if this is a
+    /// replacement.
+    fn start_transaction(
+        &mut self,
+        n: u64,
+        tx: TypedTransaction,
+        mut hashes: Vec<TxHash>,
+        retries: u32,
+        replacing: Option<(TxHash, Duration)>,
+    ) {
+        let provider = self.provider.clone();
+        let journal = self.journal.clone();
+        let sender = self.sender;
+        let nonce = tx.nonce().expect("nonce is set").as_u64();
+        let fut = async move {
+            if retries > 0 {
+                tokio::time::sleep(retry_backoff(retries)).await;
+            }
+            let sent_at = Instant::now();
+            let res = match send_signed(&provider, sender, n, &tx, journal.as_deref()).await {
+                Ok(hash) => {
+                    hashes.push(hash);
+                    Ok(TxStatus::Sent)
+                }
+                Err(err) => match mined_receipt(&provider, &hashes).await {
+                    // an earlier transaction with this nonce was mined in the meantime
+                    Some(receipt) => Ok(TxStatus::Mined(receipt)),
+                    None => Err(err),
+                },
+            };
+            InscriptionResult { n, tx, nonce, hashes, retries, sent_at, replacing, res }
+        };
+        self.pending.push(Box::pin(fut));
+    }
+
+    /// Waits until any of the transactions sent with the nonce is mined.
+    ///
+    /// If a timeout is set, the transaction is reported as stuck if it's not mined in time.
+    fn watch_transaction(
+        &mut self,
+        n: u64,
+        tx: TypedTransaction,
+        hashes: Vec<TxHash>,
+        retries: u32,
+        sent_at: Instant,
+        timeout: Option<Duration>,
+    ) {
+        let provider = self.provider.clone();
+        let nonce = tx.nonce().expect("nonce is set").as_u64();
+        let fut = async move {
+            let receipt = wait_for_receipt(&provider, &hashes);
+            let res = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, receipt).await.ok(),
+                None => Some(receipt.await),
+            };
+            let res = match res {
+                Some(Ok(Some(receipt))) => Ok(TxStatus::Mined(receipt)),
+                Some(Ok(None)) => Ok(TxStatus::Dropped),
+                Some(Err(err)) => Err(SendError::provider(err)),
+                None => Ok(TxStatus::Stuck),
+            };
+            InscriptionResult { n, tx, nonce, hashes, retries, sent_at, replacing: None, res }
+        };
+        self.pending.push(Box::pin(fut));
+    }
+
+    /// Resends the transaction after a transient error, or returns the error if sending it already
+    /// failed too often in a row.
+    fn retry(
+        &mut self,
+        n: u64,
+        tx: TypedTransaction,
+        hashes: Vec<TxHash>,
+        retries: u32,
+        replacing: Option<(TxHash, Duration)>,
+        err: BoxError,
+    ) {
+        let nonce = tx.nonce().expect("nonce is set").as_u64();
+        if retries >= self.max_retries {
+            let err = InscriberError::RetriesExhausted { nonce, retries, source: err };
+            return self.fail(n, tx, hashes, retries, err);
+        }
+        warn!(%err, %nonce, retry = retries + 1, "failed to mint; resending");
+        let mut tx = tx;
+        self.raise_fees(&mut tx);
+        self.start_transaction(n, tx, hashes, retries + 1, replacing);
+    }
+
+    /// Stops sending new transactions, the error ends the stream once the pending transactions
+    /// are done.
+    ///
+    /// Transactions that were already sent with the nonce of the failed one are still watched, as
+    /// one of them may be mined.
+    fn fail(
+        &mut self,
+        n: u64,
+        tx: TypedTransaction,
+        hashes: Vec<TxHash>,
+        retries: u32,
+        err: InscriberError,
+    ) {
+        if !hashes.is_empty() {
+            self.watch_transaction(n, tx, hashes, retries, Instant::now(), None);
+        }
+        if !self.pending.is_empty() {
+            warn!(%err, pending = self.pending.len(), "failed to mint; waiting for pending transactions");
+        }
+        self.max_transactions = self.count;
+        match &self.failure {
+            Some(_) => warn!(%err, "failed to mint"),
+            None => self.failure = Some(err),
+        }
+    }
+
+    /// Checks whether one of the transactions sent with the nonce was mined after the node
+    /// reported the nonce as used, as the receipt may not have been available yet.
+    ///
+    /// If none was mined, the nonce was used by another transaction and the result is
+    /// [SendError::NonceTooLow] without the hashes.
+    fn check_nonce(
+        &mut self,
+        n: u64,
+        tx: TypedTransaction,
+        hashes: Vec<TxHash>,
+        retries: u32,
+        next_nonce: u64,
+    ) {
+        let provider = self.provider.clone();
+        let nonce = tx.nonce().expect("nonce is set").as_u64();
+        let fut = async move {
+            tokio::time::sleep(provider.provider().get_interval()).await;
+            let sent_at = Instant::now();
+            let (hashes, res) = match mined_receipt(&provider, &hashes).await {
+                Some(receipt) => (hashes, Ok(TxStatus::Mined(receipt))),
+                None => {
+                    warn!(%nonce, ?hashes, "nonce was used by another transaction");
+                    (Vec::new(), Err(SendError::NonceTooLow { next_nonce }))
+                }
+            };
+            InscriptionResult { n, tx, nonce, hashes, retries, sent_at, replacing: None, res }
+        };
+        self.pending.push(Box::pin(fut));
+    }
+
+    /// Fetches the receipts of the transactions waiting for confirmations once the next block is
+    /// mined, see [Inscriber::update_confirmations].
+    fn check_confirmations(&mut self) {
+        let provider = self.provider.clone();
+        let checked_block = self.checked_block;
+        let hashes = self.confirming.iter().map(|mined| mined.receipt.transaction_hash).collect();
+        self.confirmation_check =
+            Some(Box::pin(async move { receipts_after(&provider, checked_block, hashes).await }));
+    }
+}
+
+impl<M> Stream for Inscriber<M>
+where
+    M: Middleware + Clone + Unpin + 'static,
+{
+    type Item = Result<InscriptionEvent, InscriberError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            for ResumedTransaction { n, tx, hashes } in std::mem::take(&mut this.resumed) {
+                let timeout = this.replacement.map(|replacement| replacement.timeout);
+                this.watch_transaction(n, tx, hashes, 0, Instant::now(), timeout);
+            }
+
+            if this.cancel.poll_cancelled(cx) && this.count < this.max_transactions {
+                debug!(sent = %this.count, "cancelled; no longer sending new transactions");
+                this.max_transactions = this.count;
+            }
+
+            if this.count >= this.max_transactions
+                && this.pending.is_empty()
+                && this.confirming.is_empty()
+                && this.confirmed.is_empty()
+            {
+                // we're done
+                return Poll::Ready(this.failure.take().map(Err));
+            }
+
+            while let Some(fee_updates) = &mut this.fee_updates {
+                match fee_updates.poll_next_unpin(cx) {
+                    Poll::Ready(Some(fees)) => this.update_fees(fees),
+                    Poll::Ready(None) => {
+                        debug!("stopped receiving fee updates");
+                        this.fee_updates = None;
+                    }
+                    Poll::Pending => break,
+                }
+            }
+
+            if let Some(event) = this.check_fee_cap() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+            if this.fee_cap_paused
+                && this.fee_updates.is_none()
+                && this.count < this.max_transactions
+            {
+                let max_fee = this.fees.max_fee();
+                let fee_cap = this.fee_cap.expect("fee cap is set");
+                warn!(%max_fee, %fee_cap, sent = %this.count, "fees exceed the fee cap and are no longer updated");
+                this.max_transactions = this.count;
+                if this.failure.is_none() {
+                    this.failure = Some(InscriberError::FeeCapExceeded { max_fee, fee_cap });
+                }
+                continue;
+            }
+
+            if let Some(watcher) = &mut this.base_fee {
+                match watcher.poll_base_fee(cx) {
+                    Poll::Ready(Some(event)) => return Poll::Ready(Some(Ok(event))),
+                    Poll::Ready(None) => {
+                        warn!("stopped receiving base fees; resuming");
+                        this.base_fee = None;
+                    }
+                    Poll::Pending => {}
+                }
+            }
+            let paused = this.fee_cap_paused
+                || this.base_fee.as_ref().is_some_and(|watcher| !watcher.is_ready());
+
+            while !paused
+                && this.pending.len() < this.concurrency
+                && this.count < this.max_transactions
+            {
+                let n = this.count;
+                let tx = this.next_transaction();
+                this.start_transaction(n, tx, Vec::new(), 0, None);
+                this.highest_nonce += 1;
+                this.count += 1;
+            }
+
+            if let Some(check) = &mut this.confirmation_check {
+                if let Poll::Ready(res) = check.as_mut().poll(cx) {
+                    this.confirmation_check = None;
+                    match res {
+                        Ok((block, receipts)) => this.update_confirmations(block, receipts),
+                        Err(err) => warn!(%err, "failed to check confirmations; retrying"),
+                    }
+                }
+            }
+            if this.confirmation_check.is_none() && !this.confirming.is_empty() {
+                this.check_confirmations();
+                continue;
+            }
+
+            let res = match this.confirmed.pop_front() {
+                Some(res) => res,
+                None if this.pending.is_empty() => {
+                    // paused or waiting for confirmations, woken up by the next base fee, fee
+                    // update or block
+                    return Poll::Pending;
+                }
+                None => match ready!(this.pending.poll_next_unpin(cx)) {
+                    Some(res) => res,
+                    None => continue,
+                },
+            };
+            let InscriptionResult { n, mut tx, nonce, hashes, retries, sent_at, replacing, res } =
+                res;
+            match res {
+                Ok(TxStatus::Sent) => {
+                    let original = hashes[0];
+                    let hash = *hashes.last().expect("transaction was sent");
+                    // the transaction was sent, so earlier failures no longer count
+                    let timeout = this.replacement.map(|replacement| replacement.timeout);
+                    this.watch_transaction(n, tx, hashes, 0, sent_at, timeout);
+                    let event = match replacing {
+                        Some((replaced, elapsed)) => {
+                            InscriptionEvent::Replaced { nonce, original, replaced, hash, elapsed }
+                        }
+                        None => InscriptionEvent::Sent { nonce, hash },
+                    };
+                    return Poll::Ready(Some(Ok(event)));
+                }
+                Ok(TxStatus::Mined(receipt)) if this.confirmations > 1 => {
+                    // checked on every new block, so it doesn't take up a concurrency slot
+                    debug!(?receipt, "mined; waiting for confirmations");
+                    this.confirming.push(MinedTransaction { n, tx, nonce, hashes, receipt });
+                }
+                Ok(TxStatus::Mined(receipt) | TxStatus::Confirmed(receipt)) => {
+                    debug!(?receipt, "confirmed");
+                    let replaces = hashes
+                        .first()
+                        .copied()
+                        .filter(|original| *original != receipt.transaction_hash);
+                    let sender = this.sender;
+                    let chain_id = this.chain_id;
+                    let calldata = tx.data().cloned().unwrap_or_default();
+                    let event = if receipt.status == Some(0u64.into()) {
+                        InscriptionEvent::Failed {
+                            nonce,
+                            receipt,
+                            sender,
+                            chain_id,
+                            calldata,
+                            replaces,
+                        }
+                    } else {
+                        InscriptionEvent::Confirmed {
+                            nonce,
+                            receipt,
+                            sender,
+                            chain_id,
+                            calldata,
+                            replaces,
+                        }
+                    };
+                    return Poll::Ready(Some(Ok(event)));
+                }
+                Ok(TxStatus::Stuck) => {
+                    let replaced = *hashes.last().expect("transaction was sent");
+                    let replacement = this
+                        .replacement
+                        .filter(|replacement| hashes.len() <= replacement.max_replacements)
+                        .and_then(|replacement| replacement.bump(&tx))
+                        .map(|mut replacement| {
+                            this.raise_fees(&mut replacement);
+                            replacement
+                        })
+                        .filter(|replacement| this.within_fee_cap(replacement));
+                    if let Some(replacement) = replacement {
+                        debug!(%nonce, hash=?replaced, "not mined in time; replacing");
+                        let replacing = Some((replaced, sent_at.elapsed()));
+                        this.start_transaction(n, replacement, hashes, 0, replacing);
+                        continue;
+                    }
+                    warn!(%nonce, hash=?replaced, "not mined in time and can't be replaced; waiting");
+                    this.watch_transaction(n, tx, hashes, retries, sent_at, None)
+                }
+                Ok(TxStatus::Reorged(receipt)) => {
+                    // the transaction is either back in the mempool or was dropped, resending
+                    // handles both
+                    let hash = receipt.transaction_hash;
+                    let block = receipt.block_number.unwrap_or_default().as_u64();
+                    let err = BoxError::from(format!("block {block} was reorged out"));
+                    this.retry(n, tx, hashes, retries, None, err);
+                    return Poll::Ready(Some(Ok(InscriptionEvent::Reorged { nonce, hash, block })));
+                }
+                Ok(TxStatus::Dropped) => {
+                    let hash = *hashes.last().expect("transaction was sent");
+                    let err = BoxError::from("transaction was dropped");
+                    this.retry(n, tx, hashes, retries, replacing, err);
+                    return Poll::Ready(Some(Ok(InscriptionEvent::Dropped { nonce, hash })));
+                }
+                Err(SendError::Retryable(err)) => {
+                    this.retry(n, tx, hashes, retries, replacing, err);
+                }
+                Err(SendError::AlreadyPending(err)) => {
+                    if hashes.is_empty() {
+                        this.retry(n, tx, hashes, retries, replacing, err);
+                    } else {
+                        debug!(%err, %nonce, "transaction already pending; waiting");
+                        this.watch_transaction(n, tx, hashes, retries, sent_at, None)
+                    }
+                }
+                Err(SendError::Provider(err)) => {
+                    // the transaction may still be mined, so keep waiting without resending it
+                    warn!(%err, %nonce, "failed to check transaction; waiting");
+                    let timeout = this
+                        .replacement
+                        .map(|replacement| replacement.timeout.saturating_sub(sent_at.elapsed()));
+                    this.watch_transaction(n, tx, hashes, retries, sent_at, timeout)
+                }
+                Err(SendError::Underpriced(err)) => {
+                    // the node requires higher fees than the latest estimate, e.g. to replace
+                    // a transaction with the same nonce
+                    let tx = match this.bump_fees(&tx) {
+                        Some(bumped) => bumped,
+                        None => {
+                            debug!(%nonce, "bumped fees would exceed the fee cap");
+                            tx
+                        }
+                    };
+                    this.retry(n, tx, hashes, retries, replacing, err);
+                }
+                Err(SendError::NonceTooLow { next_nonce }) if !hashes.is_empty() => {
+                    // an earlier transaction with the nonce may have been mined already
+                    this.check_nonce(n, tx, hashes, retries, next_nonce);
+                }
+                Err(SendError::NonceTooLow { next_nonce }) => {
+                    // the nonce was used by a transaction we didn't send, so take the next
+                    // unused one
+                    this.highest_nonce = this.highest_nonce.max(next_nonce);
+                    warn!(%nonce, new_nonce = %this.highest_nonce, "nonce too low; resyncing");
+                    while this.reserved_nonces.remove(&this.highest_nonce) {
+                        this.highest_nonce += 1;
+                    }
+                    tx.set_nonce(this.highest_nonce);
+                    this.raise_fees(&mut tx);
+                    this.highest_nonce += 1;
+                    this.start_transaction(n, tx, Vec::new(), retries, None);
+                }
+                Err(SendError::Fatal(source)) => {
+                    let err = InscriberError::Rejected { nonce, source };
+                    this.fail(n, tx, hashes, retries, err);
+                }
+            }
+        }
+    }
+}
+
+impl<M> fmt::Debug for Inscriber<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inscriber")
+            .field("pending", &self.pending.len())
+            .field("confirming", &self.confirming)
+            .field("checked_block", &self.checked_block)
+            .field("gas", &self.gas)
+            .field("fees", &self.fees)
+            .field("fee_cap", &self.fee_cap)
+            .field("fee_cap_paused", &self.fee_cap_paused)
+            .field("base_fee", &self.base_fee)
+            .field("replacement", &self.replacement)
+            .field("resumed", &self.resumed)
+            .field("reserved_nonces", &self.reserved_nonces)
+            .field("sender", &self.sender)
+            .field("count", &self.count)
+            .field("highest_nonce", &self.highest_nonce)
+            .field("max_transactions", &self.max_transactions)
+            .field("max_retries", &self.max_retries)
+            .field("confirmations", &self.confirmations)
+            .field("concurrency", &self.concurrency)
+            .field("chain_id", &self.chain_id)
+            .field("cancel", &self.cancel)
+            .field("failure", &self.failure)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Tracks the base fee of new blocks and pauses new transactions while it's above a threshold.
+struct BaseFeeWatcher {
+    /// The base fee of new blocks
+    base_fees: Pin<Box<dyn Stream<Item = U256> + Send>>,
+    /// The highest base fee to send new transactions at
+    max_base_fee: U256,
+    /// Whether new transactions are paused, `None` until the first base fee is received
+    paused: Option<bool>,
+}
+
+impl BaseFeeWatcher {
+    /// Returns true if new transactions can be sent.
+    const fn is_ready(&self) -> bool {
+        matches!(self.paused, Some(false))
+    }
+
+    /// Consumes all new base fees and returns an event if new transactions were paused or
+    /// resumed.
+    ///
+    /// Returns `Poll::Ready(None)` if no more base fees will be received.
+    fn poll_base_fee(&mut self, cx: &mut Context<'_>) -> Poll<Option<InscriptionEvent>> {
+        while let Some(base_fee) = ready!(self.base_fees.poll_next_unpin(cx)) {
+            let paused = base_fee > self.max_base_fee;
+            let was_paused = self.paused.replace(paused);
+            if paused && was_paused != Some(true) {
+                let reason = PauseReason::BaseFee { base_fee };
+                return Poll::Ready(Some(InscriptionEvent::Paused { reason }));
+            }
+            if !paused && was_paused == Some(true) {
+                let reason = PauseReason::BaseFee { base_fee };
+                return Poll::Ready(Some(InscriptionEvent::Resumed { reason }));
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+impl fmt::Debug for BaseFeeWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BaseFeeWatcher")
+            .field("max_base_fee", &self.max_base_fee)
+            .field("paused", &self.paused)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A transaction of an interrupted process, see [InscriberBuilder::resume]
+#[derive(Debug)]
+struct ResumedTransaction {
+    n: u64,
+    tx: TypedTransaction,
+    hashes: Vec<TxHash>,
+}
+
+impl ResumedTransaction {
+    /// Returns the nonce of the transaction
+    fn nonce(&self) -> Option<u64> {
+        self.tx.nonce().map(|nonce| nonce.as_u64())
+    }
+}
+
+/// Signs the transaction, records it in the journal and sends it to the node.
+async fn send_signed<M>(
+    provider: &M,
+    sender: Address,
+    n: u64,
+    tx: &TypedTransaction,
+    journal: Option<&dyn Journal>,
+) -> Result<TxHash, SendError>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    let signature = match provider.sign_transaction(tx, sender).await {
+        Ok(signature) => signature,
+        Err(err) => return Err(SendError::new(provider, sender, err).await),
+    };
+    let raw = tx.rlp_signed(&signature);
+    if let Some(journal) = journal {
+        let nonce = tx.nonce().expect("nonce is set").as_u64();
+        let hash = keccak256(&raw).into();
+        journal
+            .record(SignedTransaction { n, nonce, hash, raw: raw.clone() })
+            .await
+            .map_err(SendError::Retryable)?;
+    }
+    match provider.send_raw_transaction(raw).await {
+        Ok(pending) => Ok(*pending),
+        Err(err) => Err(SendError::new(provider, sender, err).await),
+    }
+}
+
+/// Returns the receipt of the first of the given transactions that was mined, if any
+async fn mined_receipt<M: Middleware>(
+    provider: &M,
+    hashes: &[TxHash],
+) -> Option<TransactionReceipt> {
+    for hash in hashes {
+        if let Ok(Some(receipt)) = provider.get_transaction_receipt(*hash).await {
+            return Some(receipt);
+        }
+    }
+    None
+}
+
+/// Polls the given transactions with the same nonce until one of them is mined and returns its
+/// receipt, or `None` if all of them were dropped from the mempool.
+async fn wait_for_receipt<M>(
+    provider: &M,
+    hashes: &[TxHash],
+) -> Result<Option<TransactionReceipt>, BoxError>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    let interval = provider.provider().get_interval();
+    loop {
+        tokio::time::sleep(interval).await;
+        let mut known = false;
+        for hash in hashes.iter().rev() {
+            match provider.get_transaction_receipt(*hash).await? {
+                Some(receipt) if receipt.block_number.is_some() => return Ok(Some(receipt)),
+                Some(_) => known = true,
+                None => known |= provider.get_transaction(*hash).await?.is_some(),
+            }
+        }
+        if !known {
+            return Ok(None);
+        }
+    }
+}
+
+/// The number of the block the receipts were fetched at and the receipt of every transaction, see
+/// [receipts_after]
+type ReceiptCheck = Result<(u64, Vec<(TxHash, Option<TransactionReceipt>)>), BoxError>;
+
+/// Waits for a block after `checked_block` and fetches the receipts of the transactions.
+async fn receipts_after<M>(provider: &M, checked_block: u64, hashes: Vec<TxHash>) -> ReceiptCheck
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    let interval = provider.provider().get_interval();
+    let block = loop {
+        tokio::time::sleep(interval).await;
+        let block = provider.get_block_number().await?.as_u64();
+        if block > checked_block {
+            break block;
+        }
+    };
+    let mut receipts = Vec::with_capacity(hashes.len());
+    for hash in hashes {
+        receipts.push((hash, provider.get_transaction_receipt(hash).await?));
+    }
+    Ok((block, receipts))
+}
+
+/// The outcome of a sent transaction
+#[derive(Debug)]
+enum TxStatus {
+    /// The transaction was sent and is waiting to be mined
+    Sent,
+    /// The transaction was mined, successfully or not
+    Mined(TransactionReceipt),
+    /// The transaction was mined and has the configured number of confirmations
+    Confirmed(TransactionReceipt),
+    /// The transaction was dropped from the mempool
+    Dropped,
+    /// The transaction was not mined in time
+    Stuck,
+    /// The block of the receipt was reorged out before it was confirmed
+    Reorged(TransactionReceipt),
+}
+
+/// The initial delay before resending a transaction after a transient error
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// The maximum delay before resending a transaction after a transient error
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Returns the delay before the given retry, doubling with every retry.
+fn retry_backoff(retry: u32) -> Duration {
+    let factor = 1u32 << retry.saturating_sub(1).min(16);
+    RETRY_BACKOFF.saturating_mul(factor).min(MAX_RETRY_BACKOFF)
+}
+
+/// Errors the node returns for transactions that can't succeed by resending them
+const FATAL_SEND_ERRORS: &[&str] = &["nonce too high", "insufficient funds", "invalid sender"];
+
+/// How to handle an error the node returned for a sent transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SendErrorKind {
+    /// The error is transient, e.g. the node is unavailable
+    Retryable,
+    /// The nonce was already used
+    NonceTooLow,
+    /// A transaction with the nonce is already in the mempool
+    AlreadyPending,
+    /// The fees are too low to be accepted or to replace the transaction in the mempool
+    Underpriced,
+    /// The transaction can't succeed, e.g. due to insufficient funds
+    Fatal,
+}
+
+impl SendErrorKind {
+    /// Classifies the error message of `eth_sendRawTransaction`.
+    fn from_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        if message.contains("nonce too low") {
+            SendErrorKind::NonceTooLow
+        } else if message.contains("already known") || message.contains("known transaction") {
+            SendErrorKind::AlreadyPending
+        } else if message.contains("underpriced") {
+            SendErrorKind::Underpriced
+        } else if FATAL_SEND_ERRORS.iter().any(|err| message.contains(err)) {
+            SendErrorKind::Fatal
+        } else {
+            SendErrorKind::Retryable
+        }
+    }
+}
+
+/// Why a transaction failed
+#[derive(Debug)]
+enum SendError {
+    /// Resend the transaction after a backoff
+    Retryable(BoxError),
+    /// The nonce was already used, resend the transaction with the next unused nonce
+    NonceTooLow { next_nonce: u64 },
+    /// A transaction with the nonce is already in the mempool
+    AlreadyPending(BoxError),
+    /// Resend the transaction with higher fees
+    Underpriced(BoxError),
+    /// The provider failed while waiting for the transaction, keep waiting
+    Provider(BoxError),
+    /// The transaction can't succeed
+    Fatal(BoxError),
+}
+
+impl SendError {
+    /// Classifies the error of sending a transaction and fetches the next unused nonce if the
+    /// nonce was too low.
+    async fn new<M>(provider: &M, sender: Address, err: M::Error) -> Self
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        let kind = err
+            .as_error_response()
+            .map_or(SendErrorKind::Retryable, |err| SendErrorKind::from_message(&err.message));
+        match kind {
+            SendErrorKind::Retryable => SendError::Retryable(err.into()),
+            SendErrorKind::NonceTooLow => {
+                match provider.get_transaction_count(sender, None).await {
+                    Ok(nonce) => SendError::NonceTooLow { next_nonce: nonce.as_u64() },
+                    Err(err) => SendError::Retryable(err.into()),
+                }
+            }
+            SendErrorKind::AlreadyPending => SendError::AlreadyPending(err.into()),
+            SendErrorKind::Underpriced => SendError::Underpriced(err.into()),
+            SendErrorKind::Fatal => SendError::Fatal(err.into()),
+        }
+    }
+
+    /// Returns an error of the provider while waiting for the transaction
+    fn provider(err: impl Into<BoxError>) -> Self {
+        SendError::Provider(err.into())
+    }
+}
+
+/// A mined transaction that waits for its confirmations
+#[derive(Debug)]
+struct MinedTransaction {
+    /// The index of the transaction
+    n: u64,
+    tx: TypedTransaction,
+    nonce: u64,
+    /// All transactions sent with this nonce, the original first
+    hashes: Vec<TxHash>,
+    /// The latest receipt of the mined transaction
+    receipt: TransactionReceipt,
+}
+
+impl MinedTransaction {
+    /// Returns the block the transaction is included in
+    fn block(&self) -> u64 {
+        self.receipt.block_number.unwrap_or_default().as_u64()
+    }
+}
+
+#[derive(Debug)]
+struct InscriptionResult {
+    /// The index of the transaction
+    n: u64,
+    tx: TypedTransaction,
+    nonce: u64,
+    /// All transactions sent with this nonce, the original first
+    hashes: Vec<TxHash>,
+    /// How often sending the transaction failed in a row
+    retries: u32,
+    /// When the last transaction was sent
+    sent_at: Instant,
+    /// The replaced transaction and how long it was pending, if this is a replacement
+    replacing: Option<(TxHash, Duration)>,
+    res: Result<TxStatus, SendError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bump_fees() {
+        let replacement = Replacement::new(Duration::from_secs(60)).with_fee_cap(120u64.into());
+        let tx: TypedTransaction = TransactionRequest::new().gas_price(100u64).into();
+        let bumped = replacement.bump(&tx).unwrap();
+        assert_eq!(bumped.gas_price(), Some(U256::from(110u64)));
+        assert!(replacement.bump(&bumped).is_none());
+
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .max_fee_per_gas(101u64)
+            .max_priority_fee_per_gas(1u64)
+            .into();
+        let TypedTransaction::Eip1559(bumped) = replacement.bump(&tx).unwrap() else {
+            panic!("expected eip1559 transaction")
+        };
+        assert_eq!(bumped.max_fee_per_gas, Some(U256::from(112u64)));
+        assert_eq!(bumped.max_priority_fee_per_gas, Some(U256::from(2u64)));
+    }
+
+    #[test]
+    fn classify_send_errors() {
+        assert_eq!(SendErrorKind::from_message("nonce too low"), SendErrorKind::NonceTooLow);
+        assert_eq!(SendErrorKind::from_message("already known"), SendErrorKind::AlreadyPending);
+        assert_eq!(
+            SendErrorKind::from_message("replacement transaction underpriced"),
+            SendErrorKind::Underpriced
+        );
+        assert_eq!(
+            SendErrorKind::from_message(
+                "insufficient funds for gas * price + value: address 0x00 have 0 want 1"
+            ),
+            SendErrorKind::Fatal
+        );
+        assert_eq!(
+            SendErrorKind::from_message("transaction underpriced"),
+            SendErrorKind::Underpriced
+        );
+        assert_eq!(SendErrorKind::from_message("invalid sender"), SendErrorKind::Fatal);
+        assert_eq!(SendErrorKind::from_message("header not found"), SendErrorKind::Retryable);
+
+        assert_eq!(retry_backoff(1), RETRY_BACKOFF);
+        assert_eq!(retry_backoff(3), RETRY_BACKOFF * 4);
+        assert_eq!(retry_backoff(u32::MAX), MAX_RETRY_BACKOFF);
+    }
+
+    #[test]
+    fn compute_intrinsic_gas() {
+        assert_eq!(intrinsic_gas(&[]), 21000);
+        assert_eq!(intrinsic_gas(&[0, 1, 0, 2]), 21000 + 2 * 4 + 2 * 16);
+
+        let calldata = br#"data:,{"p":"fair-20","op":"mint","tick":"brr","amt":"1000"}"#;
+        assert_eq!(intrinsic_gas(calldata), 21000 + calldata.len() as u64 * 16);
+    }
+
+    #[test]
+    fn calldata_sources() {
+        let mut calldata = Bytes::from_static(b"data:,{}");
+        assert_eq!(calldata.next_calldata(1, 2), Bytes::from_static(b"data:,{}"));
+
+        let mut calldata = |n: u64, nonce: u64| Bytes::from(format!("{n}-{nonce}").into_bytes());
+        assert_eq!(calldata.next_calldata(1, 2), Bytes::from_static(b"1-2"));
+    }
+
+    #[tokio::test]
+    async fn skip_resumed_nonces() {
+        let (provider, _) = ethers::providers::Provider::mocked();
+        let resumed: TypedTransaction = TransactionRequest::new().nonce(11u64).into();
+        let mut inscriber = Inscriber::builder(provider, |n: u64, nonce: u64| {
+            Bytes::from(format!("{n}-{nonce}").into_bytes())
+        })
+        .sender(Address::zero())
+        .nonce(10)
+        .chain_id(1)
+        .fees(Fees::Legacy { gas_price: 1u64.into() })
+        .resume(3, resumed, vec![TxHash::zero()])
+        .sent(4)
+        .max_transactions(6)
+        .build()
+        .await
+        .unwrap();
+
+        let tx = inscriber.next_transaction();
+        assert_eq!(tx.nonce(), Some(&10u64.into()));
+        assert_eq!(tx.chain_id(), Some(1u64.into()));
+        assert_eq!(tx.data(), Some(&Bytes::from_static(b"4-10")));
+        inscriber.highest_nonce += 1;
+        inscriber.count += 1;
+
+        let tx = inscriber.next_transaction();
+        assert_eq!(tx.nonce(), Some(&12u64.into()));
+        assert_eq!(tx.data(), Some(&Bytes::from_static(b"5-12")));
+    }
+
+    #[tokio::test]
+    async fn wait_for_any_receipt() {
+        let (provider, mock) = ethers::providers::Provider::mocked();
+        let provider = provider.interval(Duration::from_millis(1));
+        let (original, replacement) = (TxHash::repeat_byte(1), TxHash::repeat_byte(2));
+        let receipt = TransactionReceipt {
+            transaction_hash: original,
+            block_number: Some(1u64.into()),
+            ..Default::default()
+        };
+        // responses are returned last first
+        mock.push(receipt.clone()).unwrap();
+        mock.push(ethers::types::Transaction::default()).unwrap();
+        mock.push(serde_json::Value::Null).unwrap();
+        let mined = wait_for_receipt(&provider, &[original, replacement]).await.unwrap();
+        assert_eq!(mined, Some(receipt));
+
+        mock.push(serde_json::Value::Null).unwrap();
+        mock.push(serde_json::Value::Null).unwrap();
+        assert_eq!(wait_for_receipt(&provider, &[original]).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn reorg_before_confirmations() {
+        use ethers::types::{H256, U64};
+
+        let (provider, mock) = ethers::providers::Provider::mocked();
+        let provider = provider.interval(Duration::from_millis(1));
+        let mut inscriber = Inscriber::builder(provider, Bytes::new())
+            .sender(Address::zero())
+            .nonce(0)
+            .chain_id(1)
+            .fees(Fees::Legacy { gas_price: 1u64.into() })
+            .max_transactions(0)
+            .confirmations(3)
+            .build()
+            .await
+            .unwrap();
+        let receipt = |hash: TxHash, block: u64| TransactionReceipt {
+            transaction_hash: hash,
+            block_number: Some(block.into()),
+            block_hash: Some(H256::repeat_byte(1)),
+            ..Default::default()
+        };
+        let mined = |nonce: u64, receipt: TransactionReceipt| InscriptionResult {
+            n: nonce,
+            tx: TransactionRequest::new().nonce(nonce).into(),
+            nonce,
+            hashes: vec![receipt.transaction_hash],
+            retries: 0,
+            sent_at: Instant::now(),
+            replacing: None,
+            res: Ok(TxStatus::Mined(receipt)),
+        };
+        let (confirmed, reorged) = (TxHash::repeat_byte(1), TxHash::repeat_byte(2));
+        // responses are returned last first
+        mock.push(serde_json::Value::Null).unwrap();
+        mock.push(U64::from(13)).unwrap();
+        for block in [12u64, 11, 10] {
+            mock.push(receipt(confirmed, 10)).unwrap();
+            mock.push(U64::from(block)).unwrap();
+        }
+
+        let result = mined(0, receipt(confirmed, 10));
+        inscriber.pending.push(Box::pin(async move { result }));
+        assert!(futures::poll!(inscriber.next()).is_pending());
+        // the mined transaction no longer takes up a concurrency slot
+        assert!(inscriber.pending.is_empty());
+        assert_eq!(inscriber.confirming.len(), 1);
+
+        let event = inscriber.next().await.unwrap().unwrap();
+        assert!(matches!(event, InscriptionEvent::Confirmed { nonce: 0, .. }), "{event:?}");
+        assert_eq!(inscriber.checked_block, 12);
+
+        let result = mined(1, receipt(reorged, 12));
+        inscriber.pending.push(Box::pin(async move { result }));
+        let event = inscriber.next().await.unwrap().unwrap();
+        assert!(
+            matches!(event, InscriptionEvent::Reorged { nonce: 1, block: 12, .. }),
+            "{event:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_after_pending() {
+        let (provider, _) = ethers::providers::Provider::mocked();
+        let mut inscriber = Inscriber::builder(provider, Bytes::new())
+            .sender(Address::zero())
+            .nonce(0)
+            .chain_id(1)
+            .fees(Fees::Legacy { gas_price: 1u64.into() })
+            .max_transactions(0)
+            .build()
+            .await
+            .unwrap();
+        let result = |nonce: u64, res| InscriptionResult {
+            n: nonce,
+            tx: TransactionRequest::new().nonce(nonce).into(),
+            nonce,
+            hashes: Vec::new(),
+            retries: 0,
+            sent_at: Instant::now(),
+            replacing: None,
+            res,
+        };
+        let rejected = result(0, Err(SendError::Fatal(BoxError::from("insufficient funds"))));
+        let mined = result(1, Ok(TxStatus::Mined(TransactionReceipt::default())));
+        inscriber.pending.push(Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            mined
+        }));
+        inscriber.pending.push(Box::pin(async move { rejected }));
+
+        let event = inscriber.next().await.unwrap().unwrap();
+        assert!(matches!(event, InscriptionEvent::Confirmed { nonce: 1, .. }), "{event:?}");
+        let err = inscriber.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, InscriberError::Rejected { nonce: 0, .. }), "{err:?}");
+        assert!(inscriber.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn keep_waiting_after_provider_errors() {
+        let (provider, _) = ethers::providers::Provider::mocked();
+        let mut inscriber = Inscriber::builder(provider, Bytes::new())
+            .sender(Address::zero())
+            .nonce(0)
+            .chain_id(1)
+            .fees(Fees::Legacy { gas_price: 1u64.into() })
+            .max_transactions(0)
+            .max_retries(1)
+            .build()
+            .await
+            .unwrap();
+        let failed = InscriptionResult {
+            n: 0,
+            tx: TransactionRequest::new().nonce(0u64).into(),
+            nonce: 0,
+            hashes: vec![TxHash::random()],
+            retries: 1,
+            sent_at: Instant::now(),
+            replacing: None,
+            res: Err(SendError::provider("connection refused")),
+        };
+        inscriber.pending.push(Box::pin(async move { failed }));
+
+        // the transaction is watched again instead of failing with exhausted retries
+        let next = tokio::time::timeout(Duration::from_millis(50), inscriber.next()).await;
+        assert!(next.is_err());
+        assert_eq!(inscriber.pending.len(), 1);
+        assert!(inscriber.failure.is_none());
+    }
+
+    #[tokio::test]
+    async fn update_fees() {
+        let (provider, _) = ethers::providers::Provider::mocked();
+        let mut inscriber = Inscriber::builder(provider, Bytes::new())
+            .sender(Address::zero())
+            .nonce(0)
+            .chain_id(1)
+            .fees(Fees::Legacy { gas_price: 150u64.into() })
+            .fee_cap(100u64.into())
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(inscriber.fees(), Fees::Legacy { gas_price: 150u64.into() });
+        assert!(inscriber.exceeds_fee_cap());
+
+        inscriber.update_fees(Fees::Legacy { gas_price: 80u64.into() });
+        let mut tx = inscriber.next_transaction();
+        assert_eq!(tx.gas_price(), Some(U256::from(80u64)));
+
+        inscriber.update_fees(Fees::Legacy { gas_price: 90u64.into() });
+        inscriber.fees.raise(&mut tx);
+        assert_eq!(tx.gas_price(), Some(U256::from(90u64)));
+        assert!(inscriber.within_fee_cap(&tx));
+
+        tx.set_gas_price(101u64);
+        assert!(!inscriber.within_fee_cap(&tx));
+        inscriber.fees.raise(&mut tx);
+        assert_eq!(tx.gas_price(), Some(U256::from(101u64)));
+
+        // underpriced transactions are resent with bumped fees up to the cap
+        tx.set_gas_price(80u64);
+        let bumped = inscriber.bump_fees(&tx).unwrap();
+        assert_eq!(bumped.gas_price(), Some(U256::from(99u64)));
+        assert!(inscriber.bump_fees(&bumped).is_none());
+
+        // fees above the cap are not applied to resent transactions
+        inscriber.update_fees(Fees::Legacy { gas_price: 120u64.into() });
+        tx.set_gas_price(90u64);
+        inscriber.raise_fees(&mut tx);
+        assert_eq!(tx.gas_price(), Some(U256::from(90u64)));
+    }
+
+    #[tokio::test]
+    async fn pause_above_fee_cap() {
+        let (provider, _) = ethers::providers::Provider::mocked();
+        let (fees, fee_updates) = futures::channel::mpsc::unbounded();
+        let mut inscriber = Inscriber::builder(provider, Bytes::new())
+            .sender(Address::zero())
+            .nonce(0)
+            .chain_id(1)
+            .fees(Fees::Legacy { gas_price: 150u64.into() })
+            .fee_updates(fee_updates)
+            .fee_cap(100u64.into())
+            .max_transactions(2)
+            .build()
+            .await
+            .unwrap();
+
+        let event = inscriber.next().await.unwrap().unwrap();
+        assert!(
+            matches!(
+                event,
+                InscriptionEvent::Paused { reason: PauseReason::FeeCap { max_fee } }
+                    if max_fee == U256::from(150u64)
+            ),
+            "{event:?}"
+        );
+        let next = tokio::time::timeout(Duration::from_millis(50), inscriber.next()).await;
+        assert!(next.is_err());
+        assert_eq!(inscriber.count, 0);
+        assert!(inscriber.pending.is_empty());
+
+        fees.unbounded_send(Fees::Legacy { gas_price: 90u64.into() }).unwrap();
+        let event = inscriber.next().await.unwrap().unwrap();
+        assert!(
+            matches!(event, InscriptionEvent::Resumed { reason: PauseReason::FeeCap { .. } }),
+            "{event:?}"
+        );
+        assert_eq!(inscriber.count, 0);
+
+        // the fees can't drop below the cap anymore
+        fees.unbounded_send(Fees::Legacy { gas_price: 110u64.into() }).unwrap();
+        drop(fees);
+        let event = inscriber.next().await.unwrap().unwrap();
+        assert!(matches!(event, InscriptionEvent::Paused { .. }), "{event:?}");
+        let err = inscriber.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, InscriberError::FeeCapExceeded { .. }), "{err:?}");
+        assert_eq!(inscriber.count, 0);
+        assert!(inscriber.next().await.is_none());
+    }
+
+    #[test]
+    fn cancel_handle() {
+        let handle = CancelHandle::default();
+        let cloned = handle.clone();
+        assert!(!cloned.is_cancelled());
+        handle.cancel();
+        assert!(cloned.is_cancelled());
+    }
+}
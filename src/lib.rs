@@ -12,24 +12,58 @@
 #![deny(unused_must_use, rust_2018_idioms)]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-use ethers::types::Address;
-use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
-use serde_with::{serde_as, DisplayFromStr};
-use std::{fmt, str::FromStr, string::FromUtf8Error};
+use ethers::types::{Address, I256, U256};
+use serde::{
+    de::DeserializeOwned, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::{Map, Value};
+use std::{
+    convert::Infallible,
+    fmt,
+    ops::{Deref, DerefMut},
+    str::FromStr,
+    string::FromUtf8Error,
+};
 
+pub use amount::*;
+pub use data_uri::*;
+pub use error::*;
 pub use protocol::*;
+pub use raw::*;
+pub use rules::*;
+pub use template::*;
 
+pub mod inscriber;
+
+mod amount;
+mod data_uri;
+mod error;
 mod protocol;
+mod raw;
+mod rules;
+mod template;
 
 /// The prefix for json calldata
 pub const CALL_DATA_PREFIX: &str = "data:,";
 
 /// A helper trait for encoding inscription calldata
 pub trait InscriptionCalldata {
+    /// Returns the inscription as [DataUri] with the default [CALL_DATA_PREFIX] header
+    fn data_uri(&self) -> DataUri;
+
     /// Returns the calldata for the inscription
     ///
-    /// Note: This must be valid utf-8 and must contain the prefix [CALL_DATA_PREFIX]
-    fn calldata(&self) -> Vec<u8>;
+    /// Note: This is valid utf-8 and contains the prefix [CALL_DATA_PREFIX]
+    fn calldata(&self) -> Vec<u8> {
+        self.data_uri().encode()
+    }
+
+    /// Returns the calldata for the inscription using the mime type, parameters and encoding of
+    /// the given data URI, for example `data:application/json,`
+    fn calldata_with(&self, header: &DataUri) -> Vec<u8> {
+        let DataUri { data, .. } = self.data_uri();
+        header.clone().with_data(data).encode()
+    }
 
     /// Returns the calldata as a UTF8 string
     ///
@@ -56,10 +90,8 @@ macro_rules! impl_inscription_calldata {
     ($($t:ty),*) => {
         $(
             impl InscriptionCalldata for $t {
-                fn calldata(&self) -> Vec<u8> {
-                    let mut buf = CALL_DATA_PREFIX.as_bytes().to_vec();
-                    serde_json::to_writer(&mut buf, self).expect("Valid json");
-                    buf
+                fn data_uri(&self) -> DataUri {
+                    DataUri::new(serde_json::to_vec(self).expect("Valid json"))
                 }
             }
 
@@ -73,7 +105,303 @@ macro_rules! impl_inscription_calldata {
     };
 }
 
-impl_inscription_calldata!(Deploy, Mint, Transfer);
+impl_inscription_calldata!(Inscription, Deploy, Mint, Transfer, Burn, List);
+
+/// Any inscription, dispatched on its `op` field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inscription {
+    /// A deploy operation
+    Deploy(Deploy),
+    /// A mint operation
+    Mint(Mint),
+    /// A transfer operation
+    Transfer(Transfer),
+    /// A burn operation
+    Burn(Burn),
+    /// A list operation
+    List(List),
+    /// An inscription with an unknown or missing operation
+    Other(serde_json::Value),
+}
+
+impl Inscription {
+    /// Decodes the inscription from raw transaction input.
+    ///
+    /// The input must be a [DataUri], like `data:,{..}` or `data:application/json,{..}`, either
+    /// as raw bytes or as hex encoded string (`0x6461...`).
+    pub fn from_calldata(calldata: &[u8]) -> Result<Self, InscriptionError> {
+        let (_, uri) = decode_calldata(calldata)?;
+        Self::from_json(&uri.data)
+    }
+
+    /// Decodes the inscription from its JSON payload, like `{"p":"erc-20",..}`.
+    pub fn from_json(json: &[u8]) -> Result<Self, InscriptionError> {
+        serde_json::from_slice(json).map_err(|err| {
+            serde_json::from_slice(json)
+                .ok()
+                .as_ref()
+                .and_then(invalid_amount)
+                .unwrap_or(InscriptionError::Json(err))
+        })
+    }
+
+    /// Returns the operation of the inscription, if any.
+    pub fn op(&self) -> Option<Op> {
+        match self {
+            Inscription::Deploy(_) => Some(Op::Deploy),
+            Inscription::Mint(_) => Some(Op::Mint),
+            Inscription::Transfer(_) => Some(Op::Transfer),
+            Inscription::Burn(_) => Some(Op::Burn),
+            Inscription::List(_) => Some(Op::List),
+            Inscription::Other(value) => {
+                value.get("op").and_then(Value::as_str).map(|op| Op::Other(op.to_string()))
+            }
+        }
+    }
+
+    /// Returns the protocol of the inscription, if any.
+    pub fn protocol(&self) -> Option<Protocol> {
+        match self {
+            Inscription::Deploy(deploy) => Some(deploy.p.clone()),
+            Inscription::Mint(mint) => Some(mint.p.clone()),
+            Inscription::Transfer(transfer) => Some(transfer.p.clone()),
+            Inscription::Burn(burn) => Some(burn.p.clone()),
+            Inscription::List(list) => Some(list.p.clone()),
+            Inscription::Other(value) => value.get("p").and_then(Value::as_str).map(Protocol::from),
+        }
+    }
+
+    /// Returns the token of the inscription, if any.
+    pub fn tick(&self) -> Option<&str> {
+        match self {
+            Inscription::Deploy(deploy) => Some(&deploy.tick),
+            Inscription::Mint(mint) => Some(&mint.tick),
+            Inscription::Transfer(transfer) => Some(&transfer.tick),
+            Inscription::Burn(burn) => Some(&burn.tick),
+            Inscription::List(list) => Some(&list.tick),
+            Inscription::Other(value) => value.get("tick").and_then(Value::as_str),
+        }
+    }
+}
+
+/// Returns the error of the first amount of the inscription that fails to decode, if any.
+fn invalid_amount(inscription: &Value) -> Option<InscriptionError> {
+    let op = inscription.get("op")?.as_str()?.parse::<Op>().ok()?;
+    let amounts: &[&str] = match op {
+        Op::Deploy => &["max", "lim"],
+        Op::Mint | Op::Burn | Op::List => &["amt"],
+        Op::Transfer => {
+            return inscription
+                .get("to")?
+                .as_array()?
+                .iter()
+                .filter_map(|item| item.get("amt"))
+                .find_map(amount::signed_amount_error)
+        }
+        Op::Other(_) => return None,
+    };
+    amounts.iter().filter_map(|key| inscription.get(key)).find_map(amount::unsigned_amount_error)
+}
+
+/// Decodes raw transaction input into the calldata bytes and the [DataUri] they contain.
+///
+/// The input is either the raw bytes or a hex encoded string (`0x6461...`).
+pub(crate) fn decode_calldata(calldata: &[u8]) -> Result<(Vec<u8>, DataUri), InscriptionError> {
+    let calldata = if let Some(hex) = calldata.strip_prefix(b"0x") {
+        ethers::utils::hex::decode(hex)
+            .map_err(|err| InscriptionError::InvalidHex(err.to_string()))?
+    } else {
+        calldata.to_vec()
+    };
+    let uri = DataUri::parse(&calldata)?;
+    std::str::from_utf8(&uri.data)?;
+    Ok((calldata, uri))
+}
+
+impl From<Deploy> for Inscription {
+    fn from(deploy: Deploy) -> Self {
+        Inscription::Deploy(deploy)
+    }
+}
+
+impl From<Mint> for Inscription {
+    fn from(mint: Mint) -> Self {
+        Inscription::Mint(mint)
+    }
+}
+
+impl From<Transfer> for Inscription {
+    fn from(transfer: Transfer) -> Self {
+        Inscription::Transfer(transfer)
+    }
+}
+
+impl From<Burn> for Inscription {
+    fn from(burn: Burn) -> Self {
+        Inscription::Burn(burn)
+    }
+}
+
+impl From<List> for Inscription {
+    fn from(list: List) -> Self {
+        Inscription::List(list)
+    }
+}
+
+macro_rules! impl_try_from_inscription {
+    ($($t:ident),*) => {
+        $(
+            impl TryFrom<Inscription> for $t {
+                type Error = InscriptionError;
+
+                fn try_from(inscription: Inscription) -> Result<Self, Self::Error> {
+                    match inscription {
+                        Inscription::$t(inner) => Ok(inner),
+                        other => {
+                            Err(InscriptionError::WrongOp { expected: Op::$t, found: other.op() })
+                        }
+                    }
+                }
+            }
+
+            impl $t {
+                /// Decodes the inscription from raw transaction input, see
+                /// [Inscription::from_calldata].
+                ///
+                /// Returns [InscriptionError::WrongOp] if the inscription has another operation.
+                pub fn from_calldata(calldata: &[u8]) -> Result<Self, InscriptionError> {
+                    Inscription::from_calldata(calldata)?.try_into()
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_inscription!(Deploy, Mint, Transfer, Burn, List);
+
+impl Serialize for Inscription {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Inscription::Deploy(deploy) => deploy.serialize(serializer),
+            Inscription::Mint(mint) => mint.serialize(serializer),
+            Inscription::Transfer(transfer) => transfer.serialize(serializer),
+            Inscription::Burn(burn) => burn.serialize(serializer),
+            Inscription::List(list) => list.serialize(serializer),
+            Inscription::Other(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Inscription {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let op = value.get("op").and_then(|op| op.as_str()).and_then(|op| op.parse::<Op>().ok());
+        let inscription = match op {
+            Some(Op::Deploy) => serde_json::from_value(value).map(Inscription::Deploy),
+            Some(Op::Mint) => serde_json::from_value(value).map(Inscription::Mint),
+            Some(Op::Transfer) => serde_json::from_value(value).map(Inscription::Transfer),
+            Some(Op::Burn) => serde_json::from_value(value).map(Inscription::Burn),
+            Some(Op::List) => serde_json::from_value(value).map(Inscription::List),
+            Some(Op::Other(_)) | None => Ok(Inscription::Other(value)),
+        };
+        inscription.map_err(serde::de::Error::custom)
+    }
+}
+
+/// The additional fields of an inscription, like `nonce` or `fee`
+///
+/// A decoded inscription remembers the original order of all its keys, so it is serialized with
+/// the additional fields in the same place among the known fields.
+#[derive(Clone, Debug, Default)]
+pub struct Extra {
+    fields: Map<String, Value>,
+    /// All keys of the decoded inscription in their original order
+    order: Vec<String>,
+}
+
+impl Extra {
+    /// Returns the additional fields.
+    pub fn into_inner(self) -> Map<String, Value> {
+        self.fields
+    }
+}
+
+impl From<Map<String, Value>> for Extra {
+    fn from(fields: Map<String, Value>) -> Self {
+        Self { fields, order: Vec::new() }
+    }
+}
+
+impl Deref for Extra {
+    type Target = Map<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.fields
+    }
+}
+
+impl DerefMut for Extra {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.fields
+    }
+}
+
+/// The key order is not part of the inscription
+impl PartialEq for Extra {
+    fn eq(&self, other: &Self) -> bool {
+        self.fields == other.fields
+    }
+}
+
+impl Eq for Extra {}
+
+/// Decodes the fields of an inscription into the helper `T`, and returns all keys in their
+/// original order.
+fn deserialize_fields<'de, D, T>(deserializer: D) -> Result<(T, Vec<String>), D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let fields = Map::<String, Value>::deserialize(deserializer)?;
+    let order = fields.keys().cloned().collect();
+    let op = T::deserialize(Value::Object(fields)).map_err(serde::de::Error::custom)?;
+    Ok((op, order))
+}
+
+/// Serializes the known fields of an inscription together with its additional fields.
+///
+/// Keys of a decoded inscription keep their original order, any other fields follow after them.
+fn serialize_fields<S: Serializer>(
+    serializer: S,
+    known: Vec<(&'static str, Value)>,
+    extra: &Extra,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(known.len() + extra.len()))?;
+    for key in &extra.order {
+        if let Some((key, value)) = known.iter().find(|(known, _)| known == key) {
+            map.serialize_entry(key, value)?;
+        } else if let Some(value) = extra.get(key) {
+            map.serialize_entry(key, value)?;
+        }
+    }
+    for (key, value) in &known {
+        if !extra.order.iter().any(|decoded| decoded == key) {
+            map.serialize_entry(key, value)?;
+        }
+    }
+    for (key, value) in extra.iter() {
+        if !extra.order.contains(key) {
+            map.serialize_entry(key, value)?;
+        }
+    }
+    map.end()
+}
 
 /// Represents a deploy operation for inscribing data.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -83,9 +411,30 @@ pub struct Deploy {
     /// Represents the current token name to be deployed.
     pub tick: String,
     /// Represents the maximum issuance.
-    pub max: u64,
+    pub max: U256,
     /// Represents the maximum amount that can be mined per mining operation.
-    pub lim: u64,
+    pub lim: U256,
+    /// The number of decimals of the token, if any.
+    pub dec: Option<u8>,
+    /// Any additional fields, like `wlim` or `nonce`
+    pub extra: Extra,
+}
+
+impl Deploy {
+    /// Returns the number of decimals of the token, `0` if not set.
+    pub fn decimals(&self) -> u8 {
+        self.dec.unwrap_or_default()
+    }
+
+    /// Returns the maximum issuance in human-readable units, see [format_units].
+    pub fn max_units(&self) -> String {
+        format_units(self.max, self.decimals())
+    }
+
+    /// Returns the mint limit in human-readable units, see [format_units].
+    pub fn lim_units(&self) -> String {
+        format_units(self.lim, self.decimals())
+    }
 }
 
 impl Serialize for Deploy {
@@ -93,13 +442,17 @@ impl Serialize for Deploy {
     where
         S: Serializer,
     {
-        let mut x = serializer.serialize_struct("Deploy", 5)?;
-        x.serialize_field("p", &self.p)?;
-        x.serialize_field("op", &"deploy")?;
-        x.serialize_field("tick", &self.tick)?;
-        x.serialize_field("max", &self.max.to_string())?;
-        x.serialize_field("lim", &self.lim.to_string())?;
-        x.end()
+        let mut known = vec![
+            ("p", Value::from(self.p.to_string())),
+            ("op", Value::from("deploy")),
+            ("tick", Value::from(self.tick.as_str())),
+            ("max", Value::from(self.max.to_string())),
+            ("lim", Value::from(self.lim.to_string())),
+        ];
+        if let Some(dec) = self.dec {
+            known.push(("dec", Value::from(dec)));
+        }
+        serialize_fields(serializer, known, &self.extra)
     }
 }
 
@@ -108,26 +461,36 @@ impl<'de> Deserialize<'de> for Deploy {
     where
         D: Deserializer<'de>,
     {
-        #[serde_as]
         #[derive(Deserialize)]
         struct DeployOp {
             p: Protocol,
             op: Op,
             tick: String,
-            #[serde_as(as = "DisplayFromStr")]
-            max: u64,
-            #[serde_as(as = "DisplayFromStr")]
-            lim: u64,
+            #[serde(deserialize_with = "amount::deserialize_u256")]
+            max: U256,
+            #[serde(deserialize_with = "amount::deserialize_u256")]
+            lim: U256,
+            #[serde(default, deserialize_with = "amount::deserialize_opt_u8")]
+            dec: Option<u8>,
+            #[serde(flatten)]
+            extra: Map<String, Value>,
         }
 
-        let deploy = DeployOp::deserialize(deserializer)?;
+        let (deploy, order) = deserialize_fields::<_, DeployOp>(deserializer)?;
         if !deploy.op.is_deploy() {
-            return Err(serde::de::Error::custom(format!(
-                "Invalid operation: {}, expected deploy",
-                deploy.op
-            )));
+            return Err(serde::de::Error::custom(InscriptionError::WrongOp {
+                expected: Op::Deploy,
+                found: Some(deploy.op),
+            }));
         }
-        Ok(Deploy { p: deploy.p, tick: deploy.tick, max: deploy.max, lim: deploy.lim })
+        Ok(Deploy {
+            p: deploy.p,
+            tick: deploy.tick,
+            max: deploy.max,
+            lim: deploy.lim,
+            dec: deploy.dec,
+            extra: Extra { fields: deploy.extra, order },
+        })
     }
 }
 
@@ -141,7 +504,9 @@ pub struct Mint {
     /// The _unique_ id to use
     pub id: Option<String>,
     /// Represents the maximum amount that can be mined per mining operation.
-    pub amt: u64,
+    pub amt: U256,
+    /// Any additional fields, like `nonce`
+    pub extra: Extra,
 }
 
 impl Serialize for Mint {
@@ -149,15 +514,16 @@ impl Serialize for Mint {
     where
         S: Serializer,
     {
-        let mut x = serializer.serialize_struct("Mint", 4 + self.id.is_some() as usize)?;
-        x.serialize_field("p", &self.p)?;
-        x.serialize_field("op", &"mint")?;
-        x.serialize_field("tick", &self.tick)?;
+        let mut known = vec![
+            ("p", Value::from(self.p.to_string())),
+            ("op", Value::from("mint")),
+            ("tick", Value::from(self.tick.as_str())),
+        ];
         if let Some(id) = &self.id {
-            x.serialize_field("id", id)?;
+            known.push(("id", Value::from(id.as_str())));
         }
-        x.serialize_field("amt", &self.amt.to_string())?;
-        x.end()
+        known.push(("amt", Value::from(self.amt.to_string())));
+        serialize_fields(serializer, known, &self.extra)
     }
 }
 
@@ -166,25 +532,32 @@ impl<'de> Deserialize<'de> for Mint {
     where
         D: Deserializer<'de>,
     {
-        #[serde_as]
         #[derive(Deserialize)]
         struct MintOp {
             p: Protocol,
             op: Op,
             tick: String,
             id: Option<String>,
-            #[serde_as(as = "DisplayFromStr")]
-            amt: u64,
+            #[serde(deserialize_with = "amount::deserialize_u256")]
+            amt: U256,
+            #[serde(flatten)]
+            extra: Map<String, Value>,
         }
 
-        let mint = MintOp::deserialize(deserializer)?;
+        let (mint, order) = deserialize_fields::<_, MintOp>(deserializer)?;
         if !mint.op.is_mint() {
-            return Err(serde::de::Error::custom(format!(
-                "Invalid operation: {}, expected mint",
-                mint.op
-            )));
+            return Err(serde::de::Error::custom(InscriptionError::WrongOp {
+                expected: Op::Mint,
+                found: Some(mint.op),
+            }));
         }
-        Ok(Mint { p: mint.p, tick: mint.tick, id: mint.id, amt: mint.amt })
+        Ok(Mint {
+            p: mint.p,
+            tick: mint.tick,
+            id: mint.id,
+            amt: mint.amt,
+            extra: Extra { fields: mint.extra, order },
+        })
     }
 }
 
@@ -198,6 +571,8 @@ pub struct Transfer {
     pub tick: String,
     /// Target of the transfer
     pub to: Vec<TransferItem>,
+    /// Any additional fields, like `fee`
+    pub extra: Extra,
 }
 
 impl Serialize for Transfer {
@@ -205,12 +580,14 @@ impl Serialize for Transfer {
     where
         S: Serializer,
     {
-        let mut x = serializer.serialize_struct("Transfer", 3)?;
-        x.serialize_field("p", &self.p)?;
-        x.serialize_field("op", &"transfer")?;
-        x.serialize_field("tick", &self.tick)?;
-        x.serialize_field("to", &self.to)?;
-        x.end()
+        let to = serde_json::to_value(&self.to).map_err(serde::ser::Error::custom)?;
+        let known = vec![
+            ("p", Value::from(self.p.to_string())),
+            ("op", Value::from("transfer")),
+            ("tick", Value::from(self.tick.as_str())),
+            ("to", to),
+        ];
+        serialize_fields(serializer, known, &self.extra)
     }
 }
 
@@ -225,16 +602,154 @@ impl<'de> Deserialize<'de> for Transfer {
             op: Op,
             tick: String,
             to: Vec<TransferItem>,
+            #[serde(flatten)]
+            extra: Map<String, Value>,
         }
 
-        let transfer = TransferOp::deserialize(deserializer)?;
+        let (transfer, order) = deserialize_fields::<_, TransferOp>(deserializer)?;
         if !transfer.op.is_transfer() {
-            return Err(serde::de::Error::custom(format!(
-                "Invalid operation: {}, expected transfer",
-                transfer.op
-            )));
+            return Err(serde::de::Error::custom(InscriptionError::WrongOp {
+                expected: Op::Transfer,
+                found: Some(transfer.op),
+            }));
         }
-        Ok(Transfer { p: transfer.p, tick: transfer.tick, to: transfer.to })
+        Ok(Transfer {
+            p: transfer.p,
+            tick: transfer.tick,
+            to: transfer.to,
+            extra: Extra { fields: transfer.extra, order },
+        })
+    }
+}
+
+impl Transfer {
+    /// Returns the total amount transferred to all recipients.
+    pub fn total(&self) -> Result<I256, InscriptionError> {
+        self.to.iter().try_fold(I256::zero(), |total, item| {
+            total
+                .checked_add(item.amt)
+                .ok_or_else(|| InscriptionError::AmountOverflow(format!("{total} + {}", item.amt)))
+        })
+    }
+}
+
+/// Represents a burn operation for inscribing data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Burn {
+    /// Represents the protocol, and here the ERC-20 protocol is used.
+    pub p: Protocol,
+    /// Represents the token name.
+    pub tick: String,
+    /// The amount to burn.
+    pub amt: U256,
+    /// Any additional fields, like `fee`
+    pub extra: Extra,
+}
+
+impl Serialize for Burn {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let known = vec![
+            ("p", Value::from(self.p.to_string())),
+            ("op", Value::from("burn")),
+            ("tick", Value::from(self.tick.as_str())),
+            ("amt", Value::from(self.amt.to_string())),
+        ];
+        serialize_fields(serializer, known, &self.extra)
+    }
+}
+
+impl<'de> Deserialize<'de> for Burn {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct BurnOp {
+            p: Protocol,
+            op: Op,
+            tick: String,
+            #[serde(deserialize_with = "amount::deserialize_u256")]
+            amt: U256,
+            #[serde(flatten)]
+            extra: Map<String, Value>,
+        }
+
+        let (burn, order) = deserialize_fields::<_, BurnOp>(deserializer)?;
+        if !burn.op.is_burn() {
+            return Err(serde::de::Error::custom(InscriptionError::WrongOp {
+                expected: Op::Burn,
+                found: Some(burn.op),
+            }));
+        }
+        Ok(Burn {
+            p: burn.p,
+            tick: burn.tick,
+            amt: burn.amt,
+            extra: Extra { fields: burn.extra, order },
+        })
+    }
+}
+
+/// Represents a list operation for inscribing data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct List {
+    /// Represents the protocol, and here the ERC-20 protocol is used.
+    pub p: Protocol,
+    /// Represents the token name.
+    pub tick: String,
+    /// The amount to list.
+    pub amt: U256,
+    /// Any additional fields, like `price`
+    pub extra: Extra,
+}
+
+impl Serialize for List {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let known = vec![
+            ("p", Value::from(self.p.to_string())),
+            ("op", Value::from("list")),
+            ("tick", Value::from(self.tick.as_str())),
+            ("amt", Value::from(self.amt.to_string())),
+        ];
+        serialize_fields(serializer, known, &self.extra)
+    }
+}
+
+impl<'de> Deserialize<'de> for List {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct ListOp {
+            p: Protocol,
+            op: Op,
+            tick: String,
+            #[serde(deserialize_with = "amount::deserialize_u256")]
+            amt: U256,
+            #[serde(flatten)]
+            extra: Map<String, Value>,
+        }
+
+        let (list, order) = deserialize_fields::<_, ListOp>(deserializer)?;
+        if !list.op.is_list() {
+            return Err(serde::de::Error::custom(InscriptionError::WrongOp {
+                expected: Op::List,
+                found: Some(list.op),
+            }));
+        }
+        Ok(List {
+            p: list.p,
+            tick: list.tick,
+            amt: list.amt,
+            extra: Extra { fields: list.extra, order },
+        })
     }
 }
 
@@ -242,14 +757,27 @@ impl<'de> Deserialize<'de> for Transfer {
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TransferItem {
     /// recipient of the transfer
+    ///
+    /// Serialized as [EIP-55](https://eips.ethereum.org/EIPS/eip-55) checksummed address.
+    #[serde(serialize_with = "serialize_checksummed")]
     pub recv: Address,
     /// amount to transfer
-    // #[serde_as(as = "DisplayFromStr")] // TODO why is this apparently a number?
-    pub amt: i64,
+    ///
+    /// Serialized as decimal string, but some inscriptions encode this as JSON number.
+    #[serde(with = "amount::signed")]
+    pub amt: I256,
+}
+
+/// Serializes an address in its checksummed form instead of lowercase hex
+fn serialize_checksummed<S>(address: &Address, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&ethers::utils::to_checksum(address, None))
 }
 
 /// Represents operations for inscribing data.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Op {
     /// Deploy a new token.
     Deploy,
@@ -257,6 +785,12 @@ pub enum Op {
     Mint,
     /// Transfer a token
     Transfer,
+    /// Burn a token
+    Burn,
+    /// List a token for sale
+    List,
+    /// Any other operation, like `delist` or `proxy_transfer`
+    Other(String),
 }
 
 impl Op {
@@ -274,30 +808,48 @@ impl Op {
     pub const fn is_transfer(&self) -> bool {
         matches!(self, Op::Transfer)
     }
+
+    /// Returns true if the operation is `Burn`.
+    pub const fn is_burn(&self) -> bool {
+        matches!(self, Op::Burn)
+    }
+
+    /// Returns true if the operation is `List`.
+    pub const fn is_list(&self) -> bool {
+        matches!(self, Op::List)
+    }
+
+    /// Returns the string representation of the operation.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Op::Deploy => "deploy",
+            Op::Mint => "mint",
+            Op::Transfer => "transfer",
+            Op::Burn => "burn",
+            Op::List => "list",
+            Op::Other(op) => op,
+        }
+    }
 }
 
 impl fmt::Display for Op {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Op::Deploy => write!(f, "deploy"),
-            Op::Mint => write!(f, "mint"),
-            Op::Transfer => {
-                write!(f, "transfer")
-            }
-        }
+        self.as_str().fmt(f)
     }
 }
 
 impl FromStr for Op {
-    type Err = String;
+    type Err = Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "deploy" => Ok(Op::Deploy),
-            "mint" => Ok(Op::Mint),
-            "transfer" => Ok(Op::Transfer),
-            s => Err(format!("invalid operation: {s}")),
-        }
+        Ok(match s.to_lowercase().as_str() {
+            "deploy" => Op::Deploy,
+            "mint" => Op::Mint,
+            "transfer" => Op::Transfer,
+            "burn" => Op::Burn,
+            "list" => Op::List,
+            _ => Op::Other(s.to_string()),
+        })
     }
 }
 
@@ -306,7 +858,7 @@ impl Serialize for Op {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serializer.serialize_str(self.as_str())
     }
 }
 
@@ -348,8 +900,9 @@ mod tests {
                 tick: "osct".to_string(),
                 to: vec![TransferItem {
                     recv: "0x8D4E4Ee435a2FE82A037ba10d4486049bADbCdB2".parse().unwrap(),
-                    amt: -1000,
+                    amt: I256::from(-1000),
                 }],
+                extra: Default::default(),
             }
         );
     }
@@ -365,8 +918,14 @@ mod tests {
         let serialized_json =
             serde_json::to_string(&operation).expect("Failed to serialize to JSON");
 
-        let expected_operation =
-            Deploy { p: "erc-20".into(), tick: "gwei".to_string(), max: 21000000, lim: 1000 };
+        let expected_operation = Deploy {
+            p: "erc-20".into(),
+            tick: "gwei".to_string(),
+            max: U256::from(21000000u64),
+            lim: U256::from(1000u64),
+            dec: None,
+            extra: Default::default(),
+        };
 
         assert_eq!(operation, expected_operation);
 
@@ -375,6 +934,170 @@ mod tests {
         assert_eq!(serialized_json, expected_json);
     }
 
+    #[test]
+    fn test_large_amounts() {
+        let json_data = r#"{"p":"erc-20","op":"deploy","tick":"gwei","max":"21000000000000000000000000","lim":"1000000000000000000000","dec":18}"#;
+        let deploy: Deploy = serde_json::from_str(json_data).unwrap();
+        assert_eq!(deploy.max, U256::from_dec_str("21000000000000000000000000").unwrap());
+        assert_eq!(deploy.dec, Some(18));
+        assert_eq!(deploy.max_units(), "21000000");
+        assert_eq!(deploy.lim_units(), "1000");
+        assert_eq!(
+            deploy.calldata_string(),
+            r#"data:,{"p":"erc-20","op":"deploy","tick":"gwei","max":"21000000000000000000000000","lim":"1000000000000000000000","dec":18}"#
+        );
+        let json_data = r#"{"p":"erc-20","op":"deploy","tick":"gwei","max":21000000000000000000000000,"lim":"1000"}"#;
+        assert!(serde_json::from_str::<Deploy>(json_data).is_err());
+
+        let json_data = r#"{"p":"osc-20","op":"transfer","tick":"osct","to":[{"recv":"0x8D4E4Ee435a2FE82A037ba10d4486049bADbCdB2","amt":"21000000000000000000000"}]}"#;
+        let transfer: Transfer = serde_json::from_str(json_data).unwrap();
+        assert_eq!(transfer.to[0].amt, I256::from_dec_str("21000000000000000000000").unwrap());
+        assert_eq!(transfer.calldata_string(), format!("data:,{json_data}"));
+    }
+
+    #[test]
+    fn test_preserve_extra_fields() {
+        let json_data = r#"{"p":"erc-20","op":"deploy","tick":"gwei","max":"21000000","lim":"1000","wlim":"5000","start":18000000,"self_mint":true}"#;
+        let deploy: Deploy = serde_json::from_str(json_data).unwrap();
+        assert_eq!(deploy.extra.len(), 3);
+        assert_eq!(deploy.extra["wlim"], "5000");
+        assert_eq!(serde_json::to_string(&deploy).unwrap(), json_data);
+
+        let json_data = r#"{"p":"fair-20","op":"mint","tick":"brr","amt":"1000","nonce":"42"}"#;
+        let mint: Mint = serde_json::from_str(json_data).unwrap();
+        assert_eq!(mint.extra["nonce"], "42");
+        assert_eq!(serde_json::to_string(&mint).unwrap(), json_data);
+
+        let json_data = r#"{"p":"osc-20","op":"transfer","tick":"osct","to":[{"recv":"0x8D4E4Ee435a2FE82A037ba10d4486049bADbCdB2","amt":"1000"}],"fee":"1"}"#;
+        let transfer: Transfer = serde_json::from_str(json_data).unwrap();
+        assert_eq!(transfer.extra["fee"], "1");
+        assert_eq!(serde_json::to_string(&transfer).unwrap(), json_data);
+    }
+
+    #[test]
+    fn test_preserve_key_order() {
+        let json_data = r#"{"nonce":"7","p":"fair-20","op":"mint","tick":"brr","amt":"1000"}"#;
+        let mint: Mint = serde_json::from_str(json_data).unwrap();
+        assert_eq!(serde_json::to_string(&mint).unwrap(), json_data);
+
+        let json_data = r#"{"p":"osc-20","op":"transfer","fee":"1","tick":"osct","to":[{"recv":"0x8D4E4Ee435a2FE82A037ba10d4486049bADbCdB2","amt":"1000"}]}"#;
+        let transfer: Transfer = serde_json::from_str(json_data).unwrap();
+        assert_eq!(transfer.calldata_string(), format!("data:,{json_data}"));
+
+        let json_data = r#"{"p":"erc-20","op":"deploy","tick":"gwei","wlim":"5000","max":"21000000","lim":"1000"}"#;
+        let mut deploy: Deploy = serde_json::from_str(json_data).unwrap();
+        assert_eq!(
+            deploy,
+            Deploy {
+                p: "erc-20".into(),
+                tick: "gwei".to_string(),
+                max: U256::from(21000000u64),
+                lim: U256::from(1000u64),
+                dec: None,
+                extra: Map::from_iter([("wlim".to_string(), Value::from("5000"))]).into(),
+            }
+        );
+
+        // changed fields stay in place, new ones are appended
+        deploy.max = U256::from(42u64);
+        deploy.dec = Some(18);
+        deploy.extra.insert("start".to_string(), Value::from(18000000));
+        assert_eq!(
+            serde_json::to_string(&deploy).unwrap(),
+            r#"{"p":"erc-20","op":"deploy","tick":"gwei","wlim":"5000","max":"42","lim":"1000","dec":18,"start":18000000}"#
+        );
+    }
+
+    #[test]
+    fn test_other_ops() {
+        let calldata = r#"data:,{"p":"erc-20","op":"burn","tick":"gwei","amt":"1000"}"#;
+        let inscription = Inscription::from_calldata(calldata.as_bytes()).unwrap();
+        assert_eq!(inscription.op(), Some(Op::Burn));
+        assert_eq!(Burn::from_calldata(calldata.as_bytes()).unwrap().amt, U256::from(1000u64));
+        assert_eq!(inscription.calldata_string(), calldata);
+
+        let calldata =
+            r#"data:,{"p":"asc-20","op":"list","tick":"dino","amt":"1000","price":"100000"}"#;
+        let list = List::from_calldata(calldata.as_bytes()).unwrap();
+        assert_eq!(list.extra["price"], "100000");
+        assert_eq!(list.calldata_string(), calldata);
+
+        let calldata = r#"data:,{"p":"asc-20","op":"delist","tick":"dino","id":"0x01"}"#;
+        let inscription = Inscription::from_calldata(calldata.as_bytes()).unwrap();
+        assert_eq!(inscription.op(), Some(Op::Other("delist".to_string())));
+        assert_eq!(inscription.calldata_string(), calldata);
+    }
+
+    #[test]
+    fn test_inscription_from_calldata() {
+        let calldata = r#"data:,{"p":"fair-20","op":"mint","tick":"brr","amt":"1000"}"#;
+        let inscription = Inscription::from_calldata(calldata.as_bytes()).unwrap();
+        assert_eq!(
+            inscription,
+            Inscription::Mint(Mint {
+                p: "fair-20".into(),
+                tick: "brr".to_string(),
+                id: None,
+                amt: U256::from(1000u64),
+                extra: Default::default(),
+            })
+        );
+        assert_eq!(inscription.protocol(), Some("fair-20".into()));
+        assert_eq!(inscription.tick(), Some("brr"));
+        assert_eq!(inscription.calldata_string(), calldata);
+
+        let hex = format!("0x{}", ethers::utils::hex::encode(calldata));
+        assert_eq!(Inscription::from_calldata(hex.as_bytes()).unwrap(), inscription);
+
+        let json = r#"data:application/json;base64,eyJwIjoiZmFpci0yMCIsIm9wIjoibWludCIsInRpY2siOiJicnIiLCJhbXQiOiIxMDAwIn0="#;
+        assert_eq!(Inscription::from_calldata(json.as_bytes()).unwrap(), inscription);
+        let header = DataUri::new("").with_mime_type("application/json").with_base64(true);
+        assert_eq!(String::from_utf8(inscription.calldata_with(&header)).unwrap(), json);
+
+        let other = r#"data:,{"p":"ierc-20","op":"proxy_transfer","tick":"brr"}"#;
+        let inscription = Inscription::from_calldata(other.as_bytes()).unwrap();
+        assert!(matches!(inscription, Inscription::Other(_)));
+        assert_eq!(inscription.op(), Some(Op::Other("proxy_transfer".to_string())));
+        assert_eq!(inscription.protocol(), Some("ierc-20".into()));
+        assert_eq!(inscription.tick(), Some("brr"));
+        assert_eq!(inscription.calldata_string(), other);
+
+        assert!(matches!(
+            Inscription::from_calldata(br#"{"p":"fair-20","op":"mint"}"#),
+            Err(InscriptionError::InvalidDataUri(DataUriError::MissingScheme))
+        ));
+        assert!(matches!(
+            Inscription::from_calldata(br#"data:,{"p":"fair-20","op":"mint"}"#),
+            Err(InscriptionError::Json(_))
+        ));
+        assert!(matches!(
+            Inscription::from_calldata(
+                br#"data:,{"p":"fair-20","op":"mint","tick":"brr","amt":"1x"}"#
+            ),
+            Err(InscriptionError::InvalidAmount(_))
+        ));
+        let overflow = format!(
+            r#"data:,{{"p":"osc-20","op":"transfer","tick":"osct","to":[{{"recv":"0x8D4E4Ee435a2FE82A037ba10d4486049bADbCdB2","amt":"-{}"}}]}}"#,
+            "9".repeat(80)
+        );
+        assert!(matches!(
+            Inscription::from_calldata(overflow.as_bytes()),
+            Err(InscriptionError::AmountOverflow(_))
+        ));
+        assert!(matches!(
+            Inscription::from_calldata(b"data:,\xff"),
+            Err(InscriptionError::InvalidUtf8(_))
+        ));
+        assert!(matches!(
+            Deploy::from_calldata(calldata.as_bytes()),
+            Err(InscriptionError::WrongOp { expected: Op::Deploy, found: Some(Op::Mint) })
+        ));
+        assert!(matches!(
+            Mint::from_calldata(other.as_bytes()),
+            Err(InscriptionError::WrongOp { expected: Op::Mint, found: Some(Op::Other(_)) })
+        ));
+    }
+
     #[test]
     fn test_display_from_str() {
         // Test Display
@@ -382,8 +1105,13 @@ mod tests {
         assert_eq!(Op::Mint.to_string(), "mint");
 
         // Test FromStr
-        assert_eq!(Op::from_str("deploy"), Ok(Op::Deploy));
-        assert_eq!(Op::from_str("mint"), Ok(Op::Mint));
-        assert!(Op::from_str("invalid").is_err());
+        assert_eq!(Op::from_str("deploy").unwrap(), Op::Deploy);
+        assert_eq!(Op::from_str("mint").unwrap(), Op::Mint);
+        assert_eq!(Op::from_str("Burn").unwrap(), Op::Burn);
+        assert_eq!(
+            Op::from_str("proxy_transfer").unwrap(),
+            Op::Other("proxy_transfer".to_string())
+        );
+        assert_eq!(Op::Other("proxy_transfer".to_string()).to_string(), "proxy_transfer");
     }
 }
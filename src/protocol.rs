@@ -5,9 +5,16 @@ use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 /// An inscription protocol
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Protocol(ProtocolKind);
 
+impl Protocol {
+    /// Returns the kind of the protocol
+    pub const fn kind(&self) -> &ProtocolKind {
+        &self.0
+    }
+}
+
 impl fmt::Display for Protocol {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -46,7 +53,7 @@ impl From<NamedProtocol> for Protocol {
 }
 
 /// A protocol
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ProtocolKind {
     /// A known protocol
@@ -97,7 +104,7 @@ impl From<NamedProtocol> for ProtocolKind {
 }
 
 /// A known protocol
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::IntoStaticStr)] // Into<&'static str>, AsRef<str>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, strum::IntoStaticStr)] // Into<&'static str>, AsRef<str>
 #[derive(strum::EnumVariantNames)] // NamedProtocol::VARIANTS
 #[derive(strum::EnumString)] // FromStr, TryFrom<&str>
 #[derive(strum::EnumIter)] // NamedChain::iter
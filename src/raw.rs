@@ -0,0 +1,157 @@
+//! Inscriptions that keep their original encoding
+
+use crate::{decode_calldata, DataUri, Inscription, InscriptionCalldata, InscriptionError};
+use serde::Serialize;
+use std::{fmt, ops::Deref};
+
+/// An inscription that keeps the exact bytes it was decoded from.
+///
+/// Re-serializing a decoded inscription normalizes key order and whitespace, which changes the
+/// content hash some indexers dedupe on. This keeps the original bytes next to the parsed
+/// inscription, so they can be broadcast unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawInscription<T = Inscription> {
+    /// The original bytes, either a data URI or the bare JSON payload
+    raw: Vec<u8>,
+    /// The decoded data URI, if the original bytes are one
+    uri: Option<DataUri>,
+    /// The parsed inscription
+    inscription: T,
+}
+
+impl<T> RawInscription<T>
+where
+    T: TryFrom<Inscription>,
+    InscriptionError: From<T::Error>,
+{
+    /// Decodes the inscription from its bare JSON payload, like `{"p":"erc-20",..}`
+    pub fn from_json(json: impl Into<Vec<u8>>) -> Result<Self, InscriptionError> {
+        let raw = json.into();
+        std::str::from_utf8(&raw)?;
+        let inscription = Inscription::from_json(&raw)?.try_into()?;
+        Ok(Self { raw, uri: None, inscription })
+    }
+
+    /// Decodes the inscription from raw transaction input, see [Inscription::from_calldata].
+    ///
+    /// The data URI is kept as is, including its header.
+    pub fn from_calldata(calldata: &[u8]) -> Result<Self, InscriptionError> {
+        let (raw, uri) = decode_calldata(calldata)?;
+        let inscription = Inscription::from_json(&uri.data)?.try_into()?;
+        Ok(Self { raw, uri: Some(uri), inscription })
+    }
+}
+
+impl<T: Serialize> RawInscription<T> {
+    /// Wraps the inscription, using its JSON encoding as the original bytes
+    pub fn new(inscription: T) -> Self {
+        let raw = serde_json::to_vec(&inscription).expect("Valid json");
+        Self { raw, uri: None, inscription }
+    }
+}
+
+impl<T> RawInscription<T> {
+    /// Returns the original bytes, either a data URI or the bare JSON payload
+    pub const fn raw(&self) -> &[u8] {
+        self.raw.as_slice()
+    }
+
+    /// Returns the original JSON payload
+    pub fn payload(&self) -> &[u8] {
+        self.uri.as_ref().map_or(self.raw.as_slice(), |uri| uri.data.as_slice())
+    }
+
+    /// Returns true if the original bytes are a data URI
+    pub const fn is_data_uri(&self) -> bool {
+        self.uri.is_some()
+    }
+
+    /// Returns the parsed inscription
+    pub const fn inscription(&self) -> &T {
+        &self.inscription
+    }
+
+    /// Consumes the type and returns the parsed inscription
+    pub fn into_inner(self) -> T {
+        self.inscription
+    }
+}
+
+impl<T> Deref for RawInscription<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inscription
+    }
+}
+
+impl<T> InscriptionCalldata for RawInscription<T> {
+    fn data_uri(&self) -> DataUri {
+        self.uri.clone().unwrap_or_else(|| DataUri::new(self.raw.clone()))
+    }
+
+    /// Returns the original bytes if they are a data URI, otherwise the original payload with the
+    /// default [CALL_DATA_PREFIX](crate::CALL_DATA_PREFIX)
+    fn calldata(&self) -> Vec<u8> {
+        if self.uri.is_some() {
+            self.raw.clone()
+        } else {
+            self.data_uri().encode()
+        }
+    }
+}
+
+impl<T> fmt::Display for RawInscription<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        String::from_utf8_lossy(&self.calldata()).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Mint;
+
+    #[test]
+    fn keep_raw_bytes() {
+        let json = r#"{ "op": "mint", "p": "fair-20", "amt": "1000", "tick": "brr" }"#;
+        let raw = RawInscription::<Mint>::from_json(json).unwrap();
+        assert_eq!(raw.tick, "brr");
+        assert_eq!(raw.payload(), json.as_bytes());
+        assert_eq!(raw.calldata_string(), format!("data:,{json}"));
+        assert_eq!(
+            raw.inscription().calldata_string(),
+            r#"data:,{"op":"mint","p":"fair-20","amt":"1000","tick":"brr"}"#
+        );
+
+        let header = DataUri::new("").with_mime_type("application/json");
+        assert_eq!(
+            String::from_utf8(raw.calldata_with(&header)).unwrap(),
+            format!("data:application/json,{json}")
+        );
+
+        let uri = format!("DATA:application/json,{json}");
+        let raw = RawInscription::<Mint>::from_calldata(uri.as_bytes()).unwrap();
+        assert!(raw.is_data_uri());
+        assert_eq!(raw.payload(), json.as_bytes());
+        assert_eq!(raw.calldata_string(), uri);
+
+        let raw = RawInscription::<Inscription>::from_calldata(uri.as_bytes()).unwrap();
+        assert_eq!(raw.op(), Some(crate::Op::Mint));
+
+        assert!(RawInscription::<Mint>::from_json(r#"{"p":"fair-20","op":"mint"}"#).is_err());
+        assert!(matches!(
+            RawInscription::<crate::Deploy>::from_json(json),
+            Err(InscriptionError::WrongOp {
+                expected: crate::Op::Deploy,
+                found: Some(crate::Op::Mint)
+            })
+        ));
+        assert!(matches!(
+            RawInscription::<Mint>::from_json(
+                r#"{"p":"fair-20","op":"mint","tick":"brr","amt":-1}"#
+            ),
+            Err(InscriptionError::InvalidAmount(_))
+        ));
+    }
+}
@@ -0,0 +1,458 @@
+//! Per protocol validation rules
+
+use crate::{
+    Burn, Deploy, InscriptionError, List, Mint, NamedProtocol, Op, Protocol, ProtocolKind, Transfer,
+};
+use ethers::types::U256;
+use std::{
+    collections::HashMap,
+    fmt,
+    ops::RangeInclusive,
+    sync::{OnceLock, PoisonError, RwLock, RwLockReadGuard},
+};
+
+/// The rules of a single protocol
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    /// The allowed length of a tick in characters, if restricted
+    pub tick_len: Option<RangeInclusive<usize>>,
+    /// Whether ticks are case sensitive, otherwise ticks are compared in lowercase
+    pub case_sensitive: bool,
+    /// Whether a mint requires an `id`
+    pub mint_id_required: bool,
+    /// The maximum `lim` of a deploy, if restricted
+    pub max_lim: Option<U256>,
+    /// The operations the protocol supports, if restricted
+    pub ops: Option<Vec<Op>>,
+}
+
+impl Rules {
+    /// Sets the allowed tick length
+    pub const fn with_tick_len(mut self, tick_len: RangeInclusive<usize>) -> Self {
+        self.tick_len = Some(tick_len);
+        self
+    }
+
+    /// Sets whether ticks are case sensitive
+    pub const fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Sets whether a mint requires an `id`
+    pub const fn with_mint_id_required(mut self, mint_id_required: bool) -> Self {
+        self.mint_id_required = mint_id_required;
+        self
+    }
+
+    /// Sets the maximum `lim` of a deploy
+    pub const fn with_max_lim(mut self, max_lim: U256) -> Self {
+        self.max_lim = Some(max_lim);
+        self
+    }
+
+    /// Restricts the supported operations
+    pub fn with_ops(mut self, ops: impl Into<Vec<Op>>) -> Self {
+        self.ops = Some(ops.into());
+        self
+    }
+
+    /// Returns the tick as the protocol compares it, lowercase if ticks are not case sensitive.
+    pub fn normalize_tick(&self, tick: &str) -> String {
+        if self.case_sensitive {
+            tick.to_string()
+        } else {
+            tick.to_lowercase()
+        }
+    }
+
+    /// Ensures the protocol supports the operation
+    fn check_op(&self, protocol: &Protocol, op: Op) -> Result<(), RuleViolation> {
+        if self.ops.as_ref().is_some_and(|ops| !ops.contains(&op)) {
+            return Err(RuleViolation::UnsupportedOp { protocol: protocol.to_string(), op });
+        }
+        Ok(())
+    }
+
+    /// Ensures the tick is not empty and has an allowed length
+    ///
+    /// The length is that of the normalized tick, see [Rules::normalize_tick], because lowercasing
+    /// can change the number of characters.
+    fn check_tick(&self, tick: &str) -> Result<(), InscriptionError> {
+        if tick.trim().is_empty() {
+            return Err(InscriptionError::InvalidTick(tick.to_string()));
+        }
+        if let Some(tick_len) = &self.tick_len {
+            let len = self.normalize_tick(tick).chars().count();
+            if !tick_len.contains(&len) {
+                return Err(RuleViolation::InvalidTickLength {
+                    tick: tick.to_string(),
+                    len,
+                    expected: tick_len.clone(),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates a deploy operation
+    pub fn validate_deploy(&self, deploy: &Deploy) -> Result<(), InscriptionError> {
+        self.check_op(&deploy.p, Op::Deploy)?;
+        self.check_tick(&deploy.tick)?;
+        if let Some(max_lim) = self.max_lim {
+            if deploy.lim > max_lim {
+                return Err(RuleViolation::LimitTooHigh { lim: deploy.lim, max: max_lim }.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates a mint operation
+    pub fn validate_mint(&self, mint: &Mint) -> Result<(), InscriptionError> {
+        self.check_op(&mint.p, Op::Mint)?;
+        self.check_tick(&mint.tick)?;
+        if self.mint_id_required && mint.id.is_none() {
+            return Err(RuleViolation::MissingMintId.into());
+        }
+        Ok(())
+    }
+
+    /// Validates a transfer operation
+    pub fn validate_transfer(&self, transfer: &Transfer) -> Result<(), InscriptionError> {
+        self.check_op(&transfer.p, Op::Transfer)?;
+        self.check_tick(&transfer.tick)?;
+        transfer.total()?;
+        Ok(())
+    }
+
+    /// Validates a burn operation
+    pub fn validate_burn(&self, burn: &Burn) -> Result<(), InscriptionError> {
+        self.check_op(&burn.p, Op::Burn)?;
+        self.check_tick(&burn.tick)
+    }
+
+    /// Validates a list operation
+    pub fn validate_list(&self, list: &List) -> Result<(), InscriptionError> {
+        self.check_op(&list.p, Op::List)?;
+        self.check_tick(&list.tick)
+    }
+}
+
+impl Default for Rules {
+    /// No restrictions, case sensitive ticks and all operations are supported
+    fn default() -> Self {
+        Self {
+            tick_len: None,
+            case_sensitive: true,
+            mint_id_required: false,
+            max_lim: None,
+            ops: None,
+        }
+    }
+}
+
+/// A table of [Rules] for known and custom protocols.
+///
+/// Protocols without registered rules are not restricted, see [Rules::default]. The default
+/// table only restricts `brc-20` ticks to 4 case insensitive characters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolRules {
+    /// Rules of known protocols
+    named: HashMap<NamedProtocol, Rules>,
+    /// Rules of [ProtocolKind::Other] protocols
+    other: HashMap<String, Rules>,
+}
+
+impl ProtocolRules {
+    /// Returns an empty table without any rules
+    pub fn empty() -> Self {
+        Self { named: HashMap::new(), other: HashMap::new() }
+    }
+
+    /// Returns the shared table used by [Deploy::validate], [Mint::validate],
+    /// [Transfer::validate], [Burn::validate] and [List::validate].
+    ///
+    /// This starts with the [ProtocolRules::default] rules, see [ProtocolRules::register_global]
+    /// and [ProtocolRules::set_global] to change it.
+    ///
+    /// Note: The table is locked until the returned guard is dropped.
+    pub fn global() -> RwLockReadGuard<'static, Self> {
+        global_rules().read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Registers the rules for the given protocol in the shared table, see
+    /// [ProtocolRules::global].
+    pub fn register_global(protocol: impl Into<ProtocolKind>, rules: Rules) {
+        global_rules().write().unwrap_or_else(PoisonError::into_inner).register(protocol, rules);
+    }
+
+    /// Replaces the shared table, see [ProtocolRules::global].
+    pub fn set_global(rules: ProtocolRules) {
+        *global_rules().write().unwrap_or_else(PoisonError::into_inner) = rules;
+    }
+
+    /// Registers the rules for the given protocol, replacing any existing rules.
+    pub fn register(&mut self, protocol: impl Into<ProtocolKind>, rules: Rules) {
+        match protocol.into() {
+            ProtocolKind::Named(protocol) => {
+                self.named.insert(protocol, rules);
+            }
+            ProtocolKind::Other(protocol) => {
+                self.other.insert(protocol, rules);
+            }
+        }
+    }
+
+    /// Registers the rules for the given protocol, see [ProtocolRules::register].
+    pub fn with(mut self, protocol: impl Into<ProtocolKind>, rules: Rules) -> Self {
+        self.register(protocol, rules);
+        self
+    }
+
+    /// Returns the registered rules of the given protocol, if any.
+    pub fn get(&self, protocol: &Protocol) -> Option<&Rules> {
+        match protocol.kind() {
+            ProtocolKind::Named(protocol) => self.named.get(protocol),
+            ProtocolKind::Other(protocol) => self.other.get(protocol),
+        }
+    }
+
+    /// Returns the rules of the given protocol, or the unrestricted [Rules::default].
+    pub fn rules(&self, protocol: &Protocol) -> Rules {
+        self.get(protocol).cloned().unwrap_or_default()
+    }
+}
+
+impl Default for ProtocolRules {
+    fn default() -> Self {
+        Self::empty().with(
+            NamedProtocol::Brc_20,
+            Rules::default().with_tick_len(4..=4).with_case_sensitive(false),
+        )
+    }
+}
+
+/// The shared table of [ProtocolRules::global]
+fn global_rules() -> &'static RwLock<ProtocolRules> {
+    static RULES: OnceLock<RwLock<ProtocolRules>> = OnceLock::new();
+    RULES.get_or_init(Default::default)
+}
+
+impl Deploy {
+    /// Validates the deploy against the rules of its protocol, see [ProtocolRules::global].
+    pub fn validate(&self) -> Result<(), InscriptionError> {
+        self.validate_with(&ProtocolRules::global())
+    }
+
+    /// Validates the deploy against the rules of its protocol in the given table.
+    pub fn validate_with(&self, rules: &ProtocolRules) -> Result<(), InscriptionError> {
+        rules.rules(&self.p).validate_deploy(self)
+    }
+}
+
+impl Mint {
+    /// Validates the mint against the rules of its protocol, see [ProtocolRules::global].
+    pub fn validate(&self) -> Result<(), InscriptionError> {
+        self.validate_with(&ProtocolRules::global())
+    }
+
+    /// Validates the mint against the rules of its protocol in the given table.
+    pub fn validate_with(&self, rules: &ProtocolRules) -> Result<(), InscriptionError> {
+        rules.rules(&self.p).validate_mint(self)
+    }
+}
+
+impl Transfer {
+    /// Validates the transfer against the rules of its protocol, see [ProtocolRules::global].
+    pub fn validate(&self) -> Result<(), InscriptionError> {
+        self.validate_with(&ProtocolRules::global())
+    }
+
+    /// Validates the transfer against the rules of its protocol in the given table.
+    pub fn validate_with(&self, rules: &ProtocolRules) -> Result<(), InscriptionError> {
+        rules.rules(&self.p).validate_transfer(self)
+    }
+}
+
+impl Burn {
+    /// Validates the burn against the rules of its protocol, see [ProtocolRules::global].
+    pub fn validate(&self) -> Result<(), InscriptionError> {
+        self.validate_with(&ProtocolRules::global())
+    }
+
+    /// Validates the burn against the rules of its protocol in the given table.
+    pub fn validate_with(&self, rules: &ProtocolRules) -> Result<(), InscriptionError> {
+        rules.rules(&self.p).validate_burn(self)
+    }
+}
+
+impl List {
+    /// Validates the list against the rules of its protocol, see [ProtocolRules::global].
+    pub fn validate(&self) -> Result<(), InscriptionError> {
+        self.validate_with(&ProtocolRules::global())
+    }
+
+    /// Validates the list against the rules of its protocol in the given table.
+    pub fn validate_with(&self, rules: &ProtocolRules) -> Result<(), InscriptionError> {
+        rules.rules(&self.p).validate_list(self)
+    }
+}
+
+/// A violation of the [Rules] of a protocol
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleViolation {
+    /// The protocol does not support the operation
+    UnsupportedOp {
+        /// The protocol of the inscription
+        protocol: String,
+        /// The unsupported operation
+        op: Op,
+    },
+    /// The tick has an invalid length
+    InvalidTickLength {
+        /// The invalid tick
+        tick: String,
+        /// The length of the tick in characters
+        len: usize,
+        /// The allowed length
+        expected: RangeInclusive<usize>,
+    },
+    /// The mint has no `id`, but the protocol requires one
+    MissingMintId,
+    /// The `lim` of the deploy exceeds the maximum of the protocol
+    LimitTooHigh {
+        /// The `lim` of the deploy
+        lim: U256,
+        /// The maximum `lim`
+        max: U256,
+    },
+}
+
+impl fmt::Display for RuleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleViolation::UnsupportedOp { protocol, op } => {
+                write!(f, "{protocol} does not support {op}")
+            }
+            RuleViolation::InvalidTickLength { tick, len, expected } => {
+                if expected.start() == expected.end() {
+                    write!(
+                        f,
+                        "invalid tick {tick:?}: expected {} characters, got {len}",
+                        expected.start()
+                    )
+                } else {
+                    write!(
+                        f,
+                        "invalid tick {tick:?}: expected {}-{} characters, got {len}",
+                        expected.start(),
+                        expected.end()
+                    )
+                }
+            }
+            RuleViolation::MissingMintId => write!(f, "mint requires an id"),
+            RuleViolation::LimitTooHigh { lim, max } => {
+                write!(f, "lim {lim} exceeds the maximum of {max}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RuleViolation {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::I256;
+
+    #[test]
+    fn validate_rules() {
+        let deploy: Deploy = serde_json::from_str(
+            r#"{"p":"brc-20","op":"deploy","tick":"ordi","max":"21000000","lim":"1000"}"#,
+        )
+        .unwrap();
+        deploy.validate().unwrap();
+
+        let mut long = deploy.clone();
+        long.tick = "ordinals".to_string();
+        assert!(matches!(
+            long.validate(),
+            Err(InscriptionError::Rule(RuleViolation::InvalidTickLength { len: 8, .. }))
+        ));
+
+        long.tick = String::new();
+        assert!(matches!(long.validate(), Err(InscriptionError::InvalidTick(_))));
+
+        let mint: Mint =
+            serde_json::from_str(r#"{"p":"fair-20","op":"mint","tick":"brr","amt":"1000"}"#)
+                .unwrap();
+        mint.validate().unwrap();
+
+        let rules = ProtocolRules::default()
+            .with("fair-20", Rules::default().with_mint_id_required(true).with_ops([Op::Mint]))
+            .with("my-20", Rules::default().with_max_lim(U256::from(100u64)));
+        assert!(matches!(
+            mint.validate_with(&rules),
+            Err(InscriptionError::Rule(RuleViolation::MissingMintId))
+        ));
+
+        let mut deploy = deploy;
+        deploy.p = "fair-20".into();
+        assert!(matches!(
+            deploy.validate_with(&rules),
+            Err(InscriptionError::Rule(RuleViolation::UnsupportedOp { op: Op::Deploy, .. }))
+        ));
+
+        deploy.p = "my-20".into();
+        assert!(matches!(
+            deploy.validate_with(&rules),
+            Err(InscriptionError::Rule(RuleViolation::LimitTooHigh { .. }))
+        ));
+
+        let max = I256::MAX.to_string();
+        let transfer: Transfer = serde_json::from_str(&format!(
+            r#"{{"p":"osc-20","op":"transfer","tick":"osct","to":[{{"recv":"0x8D4E4Ee435a2FE82A037ba10d4486049bADbCdB2","amt":"{max}"}},{{"recv":"0x0000000000000000000000000000000000000001","amt":"1"}}]}}"#
+        ))
+        .unwrap();
+        assert!(matches!(transfer.validate(), Err(InscriptionError::AmountOverflow(_))));
+    }
+
+    #[test]
+    fn case_insensitive_ticks() {
+        let rules = ProtocolRules::default();
+        let brc20 = rules.rules(&"brc-20".into());
+        assert!(!brc20.case_sensitive);
+        assert_eq!(brc20.normalize_tick("ORDI"), "ordi");
+        assert_eq!(Rules::default().normalize_tick("ORDI"), "ORDI");
+
+        let mut mint: Mint =
+            serde_json::from_str(r#"{"p":"brc-20","op":"mint","tick":"OrDi","amt":"1000"}"#)
+                .unwrap();
+        mint.validate_with(&rules).unwrap();
+
+        // `İ` lowercases to two characters, so the tick is too long if compared in lowercase
+        mint.tick = "İord".to_string();
+        assert!(matches!(
+            mint.validate_with(&rules),
+            Err(InscriptionError::Rule(RuleViolation::InvalidTickLength { len: 5, .. }))
+        ));
+        let rules = rules.with(NamedProtocol::Brc_20, brc20.clone().with_case_sensitive(true));
+        mint.validate_with(&rules).unwrap();
+    }
+
+    #[test]
+    fn register_global_rules() {
+        let mint: Mint =
+            serde_json::from_str(r#"{"p":"rules-20","op":"mint","tick":"brr","amt":"1000"}"#)
+                .unwrap();
+        mint.validate().unwrap();
+
+        ProtocolRules::register_global("rules-20", Rules::default().with_tick_len(4..=4));
+        assert!(ProtocolRules::global().get(&mint.p).is_some());
+        assert!(matches!(
+            mint.validate(),
+            Err(InscriptionError::Rule(RuleViolation::InvalidTickLength { len: 3, .. }))
+        ));
+    }
+}
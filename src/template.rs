@@ -0,0 +1,196 @@
+//! Templates for values that differ per transaction
+
+use crate::InscriptionError;
+use ethers::core::rand::{thread_rng, Rng};
+use std::{fmt, fmt::Write, str::FromStr};
+
+/// The maximum number of characters of a `{random:hexN}` placeholder
+const MAX_RANDOM_HEX: usize = 64;
+
+/// A template for a value that must be unique per transaction, like the id of a mint.
+///
+/// Supported placeholders:
+///  - `{n}`: the index of the transaction, starting at 0
+///  - `{nonce}`: the nonce the transaction is first sent with
+///  - `{random:hexN}`: `N` random hex characters
+///  - `{uuid}`: a random version 4 UUID
+///
+/// Literal braces are escaped by doubling them, `{{` and `}}`.
+///
+/// Random values are not cryptographically secure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TemplatePart {
+    Text(String),
+    Index,
+    Nonce,
+    RandomHex(usize),
+    Uuid,
+}
+
+impl IdTemplate {
+    /// Renders the template for the `n`th transaction with the given nonce
+    pub fn render(&self, n: u64, nonce: u64) -> String {
+        let mut out = String::new();
+        let mut rng = thread_rng();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Text(text) => out.push_str(text),
+                TemplatePart::Index => write!(out, "{n}").unwrap(),
+                TemplatePart::Nonce => write!(out, "{nonce}").unwrap(),
+                TemplatePart::RandomHex(len) => {
+                    let start = out.len();
+                    while out.len() - start < *len {
+                        write!(out, "{:016x}", rng.gen::<u64>()).unwrap();
+                    }
+                    out.truncate(start + len);
+                }
+                TemplatePart::Uuid => {
+                    // version 4 and the RFC 4122 variant
+                    let hi = (rng.gen::<u64>() & !0xf000) | 0x4000;
+                    let lo = (rng.gen::<u64>() & !(0xc000 << 48)) | (0x8000 << 48);
+                    write!(
+                        out,
+                        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+                        hi >> 32,
+                        (hi >> 16) & 0xffff,
+                        hi & 0xffff,
+                        lo >> 48,
+                        lo & 0xffff_ffff_ffff
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        out
+    }
+
+    /// Returns true if the template has no placeholders, so every rendered value is the same
+    pub fn is_constant(&self) -> bool {
+        self.parts.iter().all(|part| matches!(part, TemplatePart::Text(_)))
+    }
+}
+
+impl FromStr for IdTemplate {
+    type Err = InscriptionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.next_if_eq(&'{').is_some() => text.push('{'),
+                '}' if chars.next_if_eq(&'}').is_some() => text.push('}'),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => {
+                                return Err(InscriptionError::InvalidTemplate(format!(
+                                    "unclosed placeholder in {s:?}"
+                                )))
+                            }
+                        }
+                    }
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(parse_placeholder(&placeholder)?);
+                }
+                '}' => {
+                    return Err(InscriptionError::InvalidTemplate(format!(
+                        "unmatched `}}` in {s:?}"
+                    )))
+                }
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(TemplatePart::Text(text));
+        }
+        Ok(Self { parts })
+    }
+}
+
+impl fmt::Display for IdTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in &self.parts {
+            match part {
+                TemplatePart::Text(text) => {
+                    f.write_str(&text.replace('{', "{{").replace('}', "}}"))?
+                }
+                TemplatePart::Index => f.write_str("{n}")?,
+                TemplatePart::Nonce => f.write_str("{nonce}")?,
+                TemplatePart::RandomHex(len) => write!(f, "{{random:hex{len}}}")?,
+                TemplatePart::Uuid => f.write_str("{uuid}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<TemplatePart, InscriptionError> {
+    match placeholder {
+        "n" => Ok(TemplatePart::Index),
+        "nonce" => Ok(TemplatePart::Nonce),
+        "uuid" => Ok(TemplatePart::Uuid),
+        _ => {
+            let len = placeholder
+                .strip_prefix("random:hex")
+                .and_then(|len| len.parse::<usize>().ok())
+                .filter(|len| (1..=MAX_RANDOM_HEX).contains(len))
+                .ok_or_else(|| {
+                    InscriptionError::InvalidTemplate(format!(
+                        "unknown placeholder {{{placeholder}}}"
+                    ))
+                })?;
+            Ok(TemplatePart::RandomHex(len))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_templates() {
+        let template: IdTemplate = "mint-{n}-{nonce}".parse().unwrap();
+        assert_eq!(template.render(3, 42), "mint-3-42");
+        assert!(!template.is_constant());
+        assert_eq!(template.to_string(), "mint-{n}-{nonce}");
+
+        let template: IdTemplate = "{{{n}}}".parse().unwrap();
+        assert_eq!(template.render(1, 0), "{1}");
+        assert_eq!(template.to_string(), "{{{n}}}");
+        assert!("static".parse::<IdTemplate>().unwrap().is_constant());
+
+        let template: IdTemplate = "{random:hex8}".parse().unwrap();
+        let id = template.render(0, 0);
+        assert_eq!(id.len(), 8);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!("{random:hex20}".parse::<IdTemplate>().unwrap().render(0, 0).len(), 20);
+
+        let template: IdTemplate = "{uuid}".parse().unwrap();
+        let uuid = template.render(0, 0);
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(uuid.split('-').map(str::len).collect::<Vec<_>>(), [8, 4, 4, 4, 12]);
+        assert_eq!(&uuid[14..15], "4");
+        assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+        assert_ne!(uuid, template.render(0, 0));
+
+        for invalid in ["{id}", "{n", "n}", "{random:hex0}", "{random:hex65}", "{random}"] {
+            assert!(matches!(
+                invalid.parse::<IdTemplate>(),
+                Err(InscriptionError::InvalidTemplate(_))
+            ));
+        }
+    }
+}